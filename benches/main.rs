@@ -1,10 +1,10 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use ecgen::{
-    combin::{comb, emk_comb_gen},
-    gray_code::brgc_gen,
-    perm::{ehr_gen, factorial, sjt_gen},
-    set_bipart::{set_bipart_gen, stirling2nd2},
-    set_partition::{set_partition_gen, stirling2nd},
+    combin::{comb, emk_comb_gen, EmkCombIter},
+    gray_code::{brgc_gen, BrgcIter},
+    perm::{ehr_gen, factorial, sjt_gen, EhrIter, EvenIter, HeapIter, LexIter, SjtIter},
+    set_bipart::{set_bipart_gen, stirling2nd2, SetBipartIter},
+    set_partition::{set_partition_gen, stirling2nd, SetPartitionIter},
 };
 
 fn bench_comb(c: &mut Criterion) {
@@ -111,6 +111,17 @@ fn bench_brgc_gen(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_brgc_iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("brgc_iter_vs_gen");
+    group.bench_function("gen n=16", |b| {
+        b.iter(|| brgc_gen(std::hint::black_box(16)).into_iter().count());
+    });
+    group.bench_function("iter n=16", |b| {
+        b.iter(|| BrgcIter::new(std::hint::black_box(16)).count());
+    });
+    group.finish();
+}
+
 fn bench_stirling2nd(c: &mut Criterion) {
     c.bench_function("stirling2nd", |b| {
         b.iter(|| stirling2nd(std::hint::black_box(30), std::hint::black_box(10)));
@@ -169,6 +180,52 @@ fn bench_set_bipart_gen(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_iterators(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterators");
+    group.bench_function("EmkCombIter n=10,k=5", |b| {
+        b.iter(|| EmkCombIter::new(std::hint::black_box(10), std::hint::black_box(5)).count());
+    });
+    group.bench_function("SjtIter n=7", |b| {
+        b.iter(|| SjtIter::new(std::hint::black_box(7)).count());
+    });
+    group.bench_function("EhrIter n=7", |b| {
+        b.iter(|| EhrIter::new(std::hint::black_box(7)).count());
+    });
+    group.bench_function("SetPartitionIter n=8,k=4", |b| {
+        b.iter(|| SetPartitionIter::new(std::hint::black_box(8), std::hint::black_box(4)).count());
+    });
+    group.bench_function("SetBipartIter n=15", |b| {
+        b.iter(|| SetBipartIter::new(std::hint::black_box(15)).count());
+    });
+    group.finish();
+}
+
+fn bench_permutation_algorithms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permutations n=8");
+    group.bench_function("sjt_gen", |b| {
+        b.iter(|| sjt_gen(std::hint::black_box(8)).into_iter().count());
+    });
+    group.bench_function("ehr_gen", |b| {
+        b.iter(|| ehr_gen(std::hint::black_box(8)).into_iter().count());
+    });
+    group.bench_function("SjtIter", |b| {
+        b.iter(|| SjtIter::new(std::hint::black_box(8)).count());
+    });
+    group.bench_function("EhrIter", |b| {
+        b.iter(|| EhrIter::new(std::hint::black_box(8)).count());
+    });
+    group.bench_function("HeapIter", |b| {
+        b.iter(|| HeapIter::new(std::hint::black_box(8)).count());
+    });
+    group.bench_function("EvenIter", |b| {
+        b.iter(|| EvenIter::new(std::hint::black_box(8)).count());
+    });
+    group.bench_function("LexIter", |b| {
+        b.iter(|| LexIter::new(std::hint::black_box(8)).count());
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_comb,
@@ -177,9 +234,12 @@ criterion_group!(
     bench_sjt_gen,
     bench_ehr_gen,
     bench_brgc_gen,
+    bench_brgc_iter,
     bench_stirling2nd,
     bench_set_partition_gen,
     bench_stirling2nd2,
-    bench_set_bipart_gen
+    bench_set_bipart_gen,
+    bench_iterators,
+    bench_permutation_algorithms
 );
 criterion_main!(benches);
@@ -1,8 +1,10 @@
 //! Property-based tests using QuickCheck for ecgen-rs
 
+use ecgen::restricted::{CyclePermutations, Derangements};
 use ecgen::{
-    brgc_gen, comb, ehr_gen, emk_comb_gen, factorial, set_bipart_gen, set_partition_gen, sjt_gen,
-    stirling2nd, stirling2nd2,
+    bell, brgc_gen, comb, cycle_type_count, derangement, ehr_gen, emk_comb_gen, factorial,
+    involution, multinomial, set_bipart_gen, set_partition_gen, sjt_gen, stirling1st, stirling2nd,
+    stirling2nd2,
 };
 use quickcheck::{Arbitrary, Gen, QuickCheck, TestResult};
 
@@ -156,6 +158,72 @@ fn factorial_correct() -> TestResult {
     TestResult::from_bool(results.iter().all(|(n, e)| factorial(*n) == *e))
 }
 
+fn stirling1st_sums_to_sjt_count(n: PermParams) -> TestResult {
+    let PermParams(n) = n;
+    let count = sjt_gen(n).into_iter().count();
+    let total: usize = (0..=n).map(|k| stirling1st(n, k)).sum();
+    TestResult::from_bool(count == total)
+}
+
+fn bell_is_sum_of_set_partition_counts(n: PermParams) -> TestResult {
+    let PermParams(n) = n;
+    if n < 3 {
+        return TestResult::discard();
+    }
+    // k = 1 and k = n contribute one partition each
+    let total: usize = (2..n)
+        .map(|k| set_partition_gen(n, k).into_iter().count() + 1)
+        .sum::<usize>()
+        + 2;
+    TestResult::from_bool(total == bell(n))
+}
+
+fn multinomial_matches_emk_count(params: CombParams) -> TestResult {
+    let CombParams(n, k) = params;
+    if k == 0 || k >= n {
+        return TestResult::discard();
+    }
+    let count = emk_comb_gen(n, k).into_iter().count() + 1;
+    TestResult::from_bool(count == multinomial(&[k, n - k]))
+}
+
+fn derangements_have_no_fixed_points(n: PermParams) -> TestResult {
+    let PermParams(n) = n;
+    let mut ders = Derangements::new(n);
+    let mut count = 0;
+    while let Some(p) = ders.advance() {
+        if p.iter().enumerate().any(|(i, &x)| x == i) {
+            return TestResult::failed();
+        }
+        count += 1;
+    }
+    TestResult::from_bool(count == derangement(n))
+}
+
+fn involutions_are_self_inverse(n: PermParams) -> TestResult {
+    let PermParams(n) = n;
+    let mut invs = CyclePermutations::involutions(n);
+    let mut count = 0;
+    while let Some(p) = invs.advance() {
+        if (0..n).any(|i| p[p[i]] != i) {
+            return TestResult::failed();
+        }
+        count += 1;
+    }
+    TestResult::from_bool(count == involution(n))
+}
+
+fn cycle_type_matches_stirling1st(n: PermParams) -> TestResult {
+    let PermParams(n) = n;
+    // A single n-cycle: there are c(n, 1) = (n - 1)! of them
+    let mut perms = CyclePermutations::with_cycle_type(&[n]);
+    let mut count = 0;
+    while perms.advance().is_some() {
+        count += 1;
+    }
+    TestResult::from_bool(count == cycle_type_count(&[n]) && count == stirling1st(n, 1))
+}
+
 fn main() {
     println!("Running quickcheck property-based tests for ecgen-rs...\n");
 
@@ -303,5 +371,59 @@ fn main() {
         Err(_) => println!("  FAILED\n"),
     }
 
+    println!("Test 17: stirling1st_sums_to_sjt_count");
+    match QuickCheck::new()
+        .tests(100)
+        .quicktest(stirling1st_sums_to_sjt_count as fn(PermParams) -> TestResult)
+    {
+        Ok(n) => println!("  Passed {}/100\n", n),
+        Err(_) => println!("  FAILED\n"),
+    }
+
+    println!("Test 18: bell_is_sum_of_set_partition_counts");
+    match QuickCheck::new()
+        .tests(100)
+        .quicktest(bell_is_sum_of_set_partition_counts as fn(PermParams) -> TestResult)
+    {
+        Ok(n) => println!("  Passed {}/100\n", n),
+        Err(_) => println!("  FAILED\n"),
+    }
+
+    println!("Test 19: multinomial_matches_emk_count");
+    match QuickCheck::new()
+        .tests(100)
+        .quicktest(multinomial_matches_emk_count as fn(CombParams) -> TestResult)
+    {
+        Ok(n) => println!("  Passed {}/100\n", n),
+        Err(_) => println!("  FAILED\n"),
+    }
+
+    println!("Test 20: derangements_have_no_fixed_points");
+    match QuickCheck::new()
+        .tests(100)
+        .quicktest(derangements_have_no_fixed_points as fn(PermParams) -> TestResult)
+    {
+        Ok(n) => println!("  Passed {}/100\n", n),
+        Err(_) => println!("  FAILED\n"),
+    }
+
+    println!("Test 21: involutions_are_self_inverse");
+    match QuickCheck::new()
+        .tests(100)
+        .quicktest(involutions_are_self_inverse as fn(PermParams) -> TestResult)
+    {
+        Ok(n) => println!("  Passed {}/100\n", n),
+        Err(_) => println!("  FAILED\n"),
+    }
+
+    println!("Test 22: cycle_type_matches_stirling1st");
+    match QuickCheck::new()
+        .tests(100)
+        .quicktest(cycle_type_matches_stirling1st as fn(PermParams) -> TestResult)
+    {
+        Ok(n) => println!("  Passed {}/100\n", n),
+        Err(_) => println!("  FAILED\n"),
+    }
+
     println!("Quickcheck integration verified!");
 }
@@ -7,9 +7,18 @@
 //! ## Key Functions
 //!
 //! - [`comb`] - Calculate binomial coefficients
+//! - [`checked_comb`] / [`checked_comb_u128`] - Binomial coefficients that report overflow
+//! - `comb_big` - Exact binomial coefficients (requires the `bigint` feature)
 //! - [`emk_comb_gen`] - Generate all k-combinations of an n-element set
 //! - [`emk_gen_even`] - Generate combinations with even k
 //! - [`emk_gen_odd`] - Generate combinations with odd k
+//! - [`EmkCombIter`] - Iterator yielding the same swaps as [`emk_comb_gen`]
+//! - [`ChaseIter`] / [`LiuTangIter`] - Chase's sequence and the Liu–Tang
+//!   revolving-door order
+//! - [`Combination`] - Stateful k-combination driven by the Eades–McKay swaps,
+//!   or in cool-lex, Chase, Liu–Tang or colex order
+//! - [`rank_comb`] / [`unrank_comb`] - Rank and unrank combinations in lex, colex or
+//!   Eades–McKay order
 //!
 //! ## Algorithm
 //!
@@ -24,11 +33,22 @@
 //!
 //! ## Complexity
 //!
-//! - `comb(n, k)`: O(min(k, n-k)) time, O(min(k, n-k)) stack space
+//! - `comb(n, k)`: O(min(k, n-k)) time, O(1) space
 //! - `emk_comb_gen(n, k)`: O(C(n,k)) output size, O(k) auxiliary space
+//! - `EmkCombIter::new(n, k)`: O(1) amortized time per swap, O(n) space
+//! - `ChaseIter::new(n, k)`: O(1) worst-case time per move, O(n) space
+//! - `LiuTangIter::new(n, k)`: O(1) amortized time per move, O(k) space
 
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::iter::FusedIterator;
+#[cfg(feature = "generator")]
 use genawaiter::sync::{Gen, GenBoxed};
 
+#[cfg(feature = "alloc")]
+use crate::multiset::MultisetIter;
+
 /// The `comb` function calculates the number of combinations of `k` elements from a set of `n`
 /// elements.
 ///
@@ -52,34 +72,139 @@ use genawaiter::sync::{Gen, GenBoxed};
 /// assert_eq!(comb(3, 2), 3);
 /// assert_eq!(comb(6, 4), comb(6, 2));
 /// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; use [`checked_comb`] to size an
+/// enumeration before starting it.
 pub const fn comb(n: usize, k: usize) -> usize {
     if k >= n || k == 0 {
         1
     } else {
-        comb_recur(n, k)
+        match checked_comb(n, k) {
+            Some(r) => r,
+            None => panic!("comb: result overflows usize"),
+        }
     }
 }
 
-/// The `comb` function calculates the number of combinations of `k` elements from a set of `n`
-/// elements.
+/// The `checked_comb` function calculates the binomial coefficient C(n, k), returning `None`
+/// instead of overflowing.
+///
+/// Unlike [`comb`], this is the mathematical binomial coefficient, so `checked_comb(n, k)` is
+/// `Some(0)` for `k > n`.
 ///
 /// Arguments:
 ///
-/// * `n`: The parameter `n` represents the total number of items or elements available for selection.
-/// * `k`: The parameter `k` represents the number of elements chosen from a set. It is used to
-///   calculate the number of combinations.
+/// * `n`: The total number of elements available for selection.
+/// * `k`: The number of elements chosen.
 ///
 /// Returns:
 ///
-/// The function `comb` returns the number of combinations of `k` elements that can be selected from a
-/// set of `n` elements.
-/// Number of combinations.
+/// `Some(C(n, k))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_comb;
+///
+/// assert_eq!(checked_comb(6, 2), Some(15));
+/// assert_eq!(checked_comb(2, 3), Some(0));
+/// assert_eq!(checked_comb(68, 34), None);
+/// ```
+pub const fn checked_comb(n: usize, k: usize) -> Option<usize> {
+    match checked_comb_u128(n as u128, k as u128) {
+        Some(r) if r <= usize::MAX as u128 => Some(r as usize),
+        _ => None,
+    }
+}
+
+/// The `checked_comb_u128` function calculates the binomial coefficient C(n, k) in `u128`,
+/// returning `None` instead of overflowing.
+///
+/// Every intermediate value is itself a binomial coefficient no larger than the result, so
+/// `None` is returned only when C(n, k) really exceeds `u128::MAX`.
+///
+/// Arguments:
+///
+/// * `n`: The total number of elements available for selection.
+/// * `k`: The number of elements chosen.
+///
+/// Returns:
+///
+/// `Some(C(n, k))`, or `None` if it does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_comb_u128;
+///
+/// assert_eq!(checked_comb_u128(68, 34), Some(28_453_041_475_240_576_740));
+/// assert_eq!(checked_comb_u128(200, 100), None);
+/// ```
+pub const fn checked_comb_u128(n: u128, k: u128) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    let k = if k < n - k { k } else { n - k };
+    let mut r: u128 = 1;
+    let mut i: u128 = 0;
+    while i < k {
+        // r = C(n, i); r * (n - i) is divisible by i + 1
+        let g = gcd(r, i + 1);
+        let r_part = r / g;
+        let n_part = (n - i) / ((i + 1) / g);
+        r = match r_part.checked_mul(n_part) {
+            Some(v) => v,
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(r)
+}
+
 #[inline]
-const fn comb_recur(n: usize, k: usize) -> usize {
-    let n = n - 1;
-    let a = if k == 1 { 1 } else { comb_recur(n, k - 1) };
-    let b = if k == n { 1 } else { comb_recur(n, k) };
-    a + b
+const fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// The `comb_big` function calculates the binomial coefficient C(n, k) exactly.
+///
+/// Arguments:
+///
+/// * `n`: The total number of elements available for selection.
+/// * `k`: The number of elements chosen.
+///
+/// Returns:
+///
+/// C(n, k) as a [`BigUint`](num_bigint::BigUint), which is zero for `k > n`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::comb_big;
+///
+/// assert_eq!(comb_big(200, 100).to_string(), "90548514656103281165404177077484163874504589675413336841320");
+/// ```
+#[cfg(feature = "bigint")]
+pub fn comb_big(n: usize, k: usize) -> num_bigint::BigUint {
+    use num_bigint::BigUint;
+
+    if k > n {
+        return BigUint::from(0u32);
+    }
+    let k = k.min(n - k);
+    let mut r = BigUint::from(1u32);
+    for i in 0..k {
+        r *= n - i;
+        r /= i + 1;
+    }
+    r
 }
 
 /// Generate all combinations by homogeneous revolving-door
@@ -113,6 +238,7 @@ const fn comb_recur(n: usize, k: usize) -> usize {
 ///
 /// assert_eq!(cnt, 3);
 /// ```
+#[cfg(feature = "generator")]
 pub fn emk_comb_gen(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if n <= k || k == 0 {
@@ -165,6 +291,7 @@ pub fn emk_comb_gen(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 /// // The count should match expected number of swaps
 /// assert!(cnt > 0);
 /// ```
+#[cfg(feature = "generator")]
 pub fn emk_gen_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k >= n - 1 {
@@ -223,6 +350,7 @@ pub fn emk_gen_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 /// // The count should match expected number of swaps
 /// assert!(cnt > 0);
 /// ```
+#[cfg(feature = "generator")]
 pub fn emk_gen_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k < n - 1 {
@@ -264,6 +392,7 @@ pub fn emk_gen_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 ///
 /// The function `emk_neg` returns a generator that yields all combinations by homogeneous
 /// revolving-door. The combinations are represented as tuples of two usize values.
+#[cfg(feature = "generator")]
 fn emk_neg_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k != 2 {
@@ -306,6 +435,7 @@ fn emk_neg_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 ///
 /// The function `emk_neg` returns a generator that yields all combinations by homogeneous
 /// revolving-door. The combinations are represented as tuples of two usize values.
+#[cfg(feature = "generator")]
 fn emk_neg_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k == 3 {
@@ -332,6 +462,1269 @@ fn emk_neg_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     })
 }
 
+/// The four mutually recursive lists of the Eades–McKay algorithm.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum EmkKind {
+    GenEven,
+    GenOdd,
+    NegEven,
+    NegOdd,
+}
+
+/// A suspended call of `emk_gen_even`, `emk_gen_odd`, `emk_neg_even` or
+/// `emk_neg_odd`: `pc` marks where to resume and `i` is the loop variable.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct EmkFrame {
+    kind: EmkKind,
+    n: usize,
+    k: usize,
+    pc: u8,
+    i: usize,
+}
+
+#[cfg(feature = "alloc")]
+enum EmkStep {
+    Yield((usize, usize)),
+    Call(EmkKind, usize, usize),
+    Return,
+}
+
+#[cfg(feature = "alloc")]
+impl EmkFrame {
+    fn new(kind: EmkKind, n: usize, k: usize) -> Self {
+        Self {
+            kind,
+            n,
+            k,
+            pc: 0,
+            i: 0,
+        }
+    }
+
+    /// Runs the frame until it yields a swap, calls a sublist or returns.
+    fn step(&mut self) -> EmkStep {
+        use EmkKind::*;
+        use EmkStep::*;
+        let (n, k) = (self.n, self.k);
+        loop {
+            match (self.kind, self.pc) {
+                (GenEven, 0) => {
+                    if k >= n - 1 {
+                        self.pc = 3;
+                        return Yield((n - 2, n - 1));
+                    }
+                    self.pc = 1;
+                    return Call(GenEven, n - 1, k);
+                }
+                (GenEven, 1) => {
+                    self.pc = 2;
+                    return Yield((n - 2, n - 1));
+                }
+                (GenEven, 2) => {
+                    if k == 2 {
+                        self.i = n - 3;
+                        self.pc = 6;
+                        continue;
+                    }
+                    self.pc = 3;
+                    return Call(NegOdd, n - 2, k - 1);
+                }
+                (GenEven, 6) | (NegOdd, 6) => {
+                    if self.i > 0 {
+                        self.i -= 1;
+                        return Yield((self.i + 1, self.i));
+                    }
+                    self.pc = if self.kind == GenEven { 3 } else { 1 };
+                }
+                (GenEven, 3) => {
+                    self.pc = 4;
+                    return Yield((k - 2, n - 2));
+                }
+                (GenEven, 4) => {
+                    if k == 2 {
+                        return Return;
+                    }
+                    self.pc = 5;
+                    return Call(GenEven, n - 2, k - 2);
+                }
+                (GenOdd, 0) => {
+                    if k < n - 1 {
+                        self.pc = 1;
+                        return Call(GenOdd, n - 1, k);
+                    }
+                    self.pc = 3;
+                    return Yield((n - 2, n - 1));
+                }
+                (GenOdd, 1) => {
+                    self.pc = 2;
+                    return Yield((n - 2, n - 1));
+                }
+                (GenOdd, 2) => {
+                    self.pc = 3;
+                    return Call(NegEven, n - 2, k - 1);
+                }
+                (GenOdd, 3) => {
+                    self.pc = 4;
+                    return Yield((k - 2, n - 2));
+                }
+                (GenOdd, 4) => {
+                    if k == 3 {
+                        self.i = 0;
+                        self.pc = 6;
+                        continue;
+                    }
+                    self.pc = 5;
+                    return Call(GenOdd, n - 2, k - 2);
+                }
+                (GenOdd, 6) | (NegEven, 6) => {
+                    if self.i < n - 3 {
+                        self.i += 1;
+                        return Yield((self.i - 1, self.i));
+                    }
+                    if self.kind == GenOdd {
+                        return Return;
+                    }
+                    self.pc = 3;
+                }
+                (NegEven, 0) => {
+                    self.pc = 1;
+                    if k != 2 {
+                        return Call(NegEven, n - 2, k - 2);
+                    }
+                }
+                (NegEven, 1) => {
+                    self.pc = 2;
+                    return Yield((n - 2, k - 2));
+                }
+                (NegEven, 2) => {
+                    if k >= n - 1 {
+                        self.pc = 5;
+                        return Yield((n - 1, n - 2));
+                    }
+                    if k == 2 {
+                        self.i = 0;
+                        self.pc = 6;
+                        continue;
+                    }
+                    self.pc = 3;
+                    return Call(GenOdd, n - 2, k - 1);
+                }
+                (NegEven, 3) => {
+                    self.pc = 4;
+                    return Yield((n - 1, n - 2));
+                }
+                (NegEven, 4) => {
+                    self.pc = 5;
+                    return Call(NegEven, n - 1, k);
+                }
+                (NegOdd, 0) => {
+                    if k == 3 {
+                        self.i = n - 3;
+                        self.pc = 6;
+                        continue;
+                    }
+                    self.pc = 1;
+                    return Call(NegOdd, n - 2, k - 2);
+                }
+                (NegOdd, 1) => {
+                    self.pc = 2;
+                    return Yield((n - 2, k - 2));
+                }
+                (NegOdd, 2) => {
+                    if k >= n - 1 {
+                        self.pc = 5;
+                        return Yield((n - 1, n - 2));
+                    }
+                    self.pc = 3;
+                    return Call(GenEven, n - 2, k - 1);
+                }
+                (NegOdd, 3) => {
+                    self.pc = 4;
+                    return Yield((n - 1, n - 2));
+                }
+                (NegOdd, 4) => {
+                    self.pc = 5;
+                    return Call(NegOdd, n - 1, k);
+                }
+                _ => return Return,
+            }
+        }
+    }
+
+    /// Whether `stack` is a chain of calls starting with the call `root`: each
+    /// frame is at a point that its list reaches, and each frame below the top
+    /// one has just made the call of the frame above it.
+    #[cfg(feature = "serde")]
+    fn is_call_chain(stack: &[Self], root: (EmkKind, usize, usize)) -> bool {
+        let mut call = Some(root);
+        for (depth, frame) in stack.iter().enumerate() {
+            if call != Some((frame.kind, frame.n, frame.k)) {
+                return false;
+            }
+            let top = depth + 1 == stack.len();
+            let mut run = Self::new(frame.kind, frame.n, frame.k);
+            let mut made = None;
+            call = loop {
+                if (run.pc, run.i) == (frame.pc, frame.i) && (top || made.is_some()) {
+                    break made;
+                }
+                made = match run.step() {
+                    EmkStep::Yield(_) => None,
+                    EmkStep::Call(kind, n, k) => Some((kind, n, k)),
+                    EmkStep::Return => return false,
+                };
+            };
+        }
+        true
+    }
+}
+
+/// Iterator over the swaps of the homogeneous revolving-door algorithm
+///
+/// `EmkCombIter` yields exactly the same swaps as [`emk_comb_gen`], starting from
+/// the configuration `1^k 0^(n-k)`. The mutual recursion of `emk_gen_even`,
+/// `emk_gen_odd`, `emk_neg_even` and `emk_neg_odd` is run on an explicit stack
+/// of frames, so no generator is boxed per recursion level.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::combin::EmkCombIter;
+///
+/// let mut combin = [1, 1, 0];
+/// let mut cnt = 1;
+/// for (i, j) in EmkCombIter::new(3, 2) {
+///     combin.swap(i, j);
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 3);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "EmkCombState")
+)]
+pub struct EmkCombIter {
+    stack: Vec<EmkFrame>,
+    /// Next swap for `k == 1`, where the single element walks to the right.
+    walk: Option<(usize, usize)>,
+    n: usize,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct EmkCombState {
+    stack: Vec<EmkFrame>,
+    walk: Option<(usize, usize)>,
+    n: usize,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<EmkCombState> for EmkCombIter {
+    type Error = &'static str;
+
+    fn try_from(state: EmkCombState) -> Result<Self, Self::Error> {
+        let EmkCombState {
+            stack,
+            walk,
+            n,
+            remaining,
+        } = state;
+        let valid = match (stack.first(), walk) {
+            (None, None) => true,
+            (None, Some((i, j))) => j == i + 1 && j < n,
+            (Some(root), None) => {
+                let k = root.k;
+                let kind = if k % 2 == 0 {
+                    EmkKind::GenEven
+                } else {
+                    EmkKind::GenOdd
+                };
+                k > 1 && k < n && EmkFrame::is_call_chain(&stack, (kind, n, k))
+            }
+            (Some(_), Some(_)) => false,
+        };
+        if !valid {
+            return Err("invalid EmkCombIter state");
+        }
+        Ok(Self {
+            stack,
+            walk,
+            n,
+            remaining,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EmkCombIter {
+    /// Creates the iterator for the k-combinations of an n-element set.
+    ///
+    /// The iterator is empty unless `0 < k < n`.
+    pub fn new(n: usize, k: usize) -> Self {
+        let mut stack = Vec::new();
+        let mut walk = None;
+        if k == 1 && n > 1 {
+            walk = Some((0, 1));
+        } else if k > 1 && k < n {
+            let kind = if k % 2 == 0 {
+                EmkKind::GenEven
+            } else {
+                EmkKind::GenOdd
+            };
+            stack.push(EmkFrame::new(kind, n, k));
+        }
+        let remaining = if k == 0 || k >= n {
+            Some(0)
+        } else {
+            checked_comb(n, k).map(|r| r - 1)
+        };
+        Self {
+            stack,
+            walk,
+            n,
+            remaining,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for EmkCombIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let swap = if let Some((i, j)) = self.walk {
+            self.walk = if j + 1 < self.n {
+                Some((j, j + 1))
+            } else {
+                None
+            };
+            (i, j)
+        } else {
+            loop {
+                match self.stack.last_mut()?.step() {
+                    EmkStep::Yield(swap) => break swap,
+                    EmkStep::Call(kind, n, k) => self.stack.push(EmkFrame::new(kind, n, k)),
+                    EmkStep::Return => {
+                        self.stack.pop();
+                    }
+                }
+            }
+        };
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some(swap)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for EmkCombIter {}
+
+/// Iterator over Chase's sequence of k-combinations
+///
+/// Starting from the configuration `1^k 0^(n-k)`, each item `(i, j)` moves the
+/// element at position `i` to the empty position `j`, where `|i - j| <= 2`.
+/// All `comb(n, k) - 1` moves are yielded, so every k-combination is visited
+/// exactly once, in O(1) worst-case time per move.
+///
+/// This is Algorithm C of Knuth, TAOCP Vol. 4A, Section 7.2.1.3, run on the
+/// bits in reverse order.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::combin::ChaseIter;
+///
+/// let mut combin = [1, 1, 0, 0];
+/// let mut cnt = 1;
+/// for (i, j) in ChaseIter::new(4, 2) {
+///     assert!(i.abs_diff(j) <= 2);
+///     combin.swap(i, j);
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 6);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ChaseState")
+)]
+pub struct ChaseIter {
+    /// `a[j]` is the bit at position `n - 1 - j`.
+    a: Vec<bool>,
+    w: Vec<bool>,
+    r: usize,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct ChaseState {
+    a: Vec<bool>,
+    w: Vec<bool>,
+    r: usize,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<ChaseState> for ChaseIter {
+    type Error = &'static str;
+
+    fn try_from(state: ChaseState) -> Result<Self, Self::Error> {
+        let ChaseState {
+            a,
+            w,
+            r,
+            remaining,
+            done,
+        } = state;
+        let iter = Self {
+            a,
+            w,
+            r,
+            remaining,
+            done,
+        };
+        let sized = !iter.w.is_empty() && iter.a.len() == iter.w.len();
+        if !sized || !(iter.done || iter.is_reachable()) {
+            return Err("invalid ChaseIter state");
+        }
+        Ok(iter)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ChaseIter {
+    /// Creates the iterator for the k-combinations of an n-element set.
+    ///
+    /// The iterator is empty unless `0 < k < n`.
+    pub fn new(n: usize, k: usize) -> Self {
+        let done = k == 0 || k >= n;
+        let s = n.saturating_sub(k);
+        let mut a = vec![false; n + 1];
+        a[s..n].fill(true);
+        Self {
+            a,
+            w: vec![true; n + 1],
+            r: s,
+            remaining: if done {
+                Some(0)
+            } else {
+                checked_comb(n, k).map(|r| r - 1)
+            },
+            done,
+        }
+    }
+
+    /// Whether Algorithm C reaches this state while visiting the bits `a`.
+    ///
+    /// The combinations that agree on the bits above `j` form a run of
+    /// Chase's sequence or of its dual, in which bit `j` starts out as 1 or 0,
+    /// respectively; `w[j]` is cleared once bit `j` has changed within its
+    /// run. Below a 1 the run is of the same kind as the one above, below a 0
+    /// it is the dual exactly when bits `0..=j` are odd in number. Finally, `r`
+    /// is the least `j > 0` with `a[j] != a[0]`.
+    #[cfg(feature = "serde")]
+    fn is_reachable(&self) -> bool {
+        let n = self.w.len() - 1;
+        let k = self.a[..n].iter().filter(|&&b| b).count();
+        if k == 0 || k >= n || self.a[n] || !self.w[n] {
+            return false;
+        }
+        // zeros and ones among a[..=j], and the kind of the run of bit j
+        let (mut s, mut t, mut dual) = (n - k, k, false);
+        for j in (0..n).rev() {
+            let unchanged = s == 0 || t == 0 || self.a[j] != dual;
+            if self.w[j] != unchanged {
+                return false;
+            }
+            if self.a[j] {
+                t -= 1;
+            } else {
+                s -= 1;
+                dual = j % 2 == 0;
+            }
+        }
+        (1..n).find(|&j| self.a[j] != self.a[0]) == Some(self.r)
+    }
+
+    /// Moves the bit at `a[from]` to `a[to]` and returns the move as positions.
+    fn moved(&mut self, from: usize, to: usize) -> (usize, usize) {
+        self.a[from] = false;
+        self.a[to] = true;
+        let n = self.w.len() - 1;
+        (n - 1 - from, n - 1 - to)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for ChaseIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        if self.done {
+            return None;
+        }
+        let n = self.w.len() - 1;
+        let mut j = self.r;
+        while !self.w[j] {
+            self.w[j] = true;
+            j += 1;
+        }
+        if j == n {
+            self.done = true;
+            return None;
+        }
+        self.w[j] = false;
+        let r = self.r;
+        let odd = j % 2 == 1;
+        let swap = if self.a[j] {
+            if odd || self.a[j - 2] {
+                // move right one
+                if r == j && j > 1 {
+                    self.r = j - 1;
+                } else if r == j - 1 {
+                    self.r = j;
+                }
+                self.moved(j, j - 1)
+            } else {
+                // move right two
+                if r == j {
+                    self.r = (j - 2).max(1);
+                } else if r == j - 2 {
+                    self.r = j - 1;
+                }
+                self.moved(j, j - 2)
+            }
+        } else if !odd || self.a[j - 1] {
+            // move left one
+            if r == j && j > 1 {
+                self.r = j - 1;
+            } else if r == j - 1 {
+                self.r = j;
+            }
+            self.moved(j - 1, j)
+        } else {
+            // move left two
+            if r == j - 2 {
+                self.r = j;
+            } else if r == j - 1 {
+                self.r = j - 2;
+            }
+            self.moved(j - 2, j)
+        };
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some(swap)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for ChaseIter {}
+
+/// Iterator over the Liu–Tang revolving-door order of k-combinations
+///
+/// Starting from the subset `{0, 1, ..., k-1}`, each item `(i, j)` removes the
+/// element `i` and adds the element `j`. All `comb(n, k) - 1` moves are
+/// yielded, so every k-combination is visited exactly once. Unlike
+/// [`EmkCombIter`], the moves are not homogeneous: `i` and `j` may lie on
+/// either side of other elements of the subset.
+///
+/// This is Algorithm R of Knuth, TAOCP Vol. 4A, Section 7.2.1.3, which takes
+/// O(1) amortized time per move.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::combin::LiuTangIter;
+///
+/// let moves: Vec<_> = LiuTangIter::new(4, 2).collect();
+/// // 01, 12, 02, 23, 13, 03
+/// assert_eq!(moves, [(0, 2), (1, 0), (0, 3), (2, 1), (1, 0)]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "LiuTangState")
+)]
+pub struct LiuTangIter {
+    /// `c[1..=k]` is the subset in increasing order, with `c[k + 1] = n`.
+    c: Vec<usize>,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct LiuTangState {
+    c: Vec<usize>,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<LiuTangState> for LiuTangIter {
+    type Error = &'static str;
+
+    fn try_from(state: LiuTangState) -> Result<Self, Self::Error> {
+        let LiuTangState { c, remaining, done } = state;
+        // every increasing subset is visited, so any one can be resumed
+        let valid = c.len() >= 2 && (done || c.len() > 2 && c[1..].windows(2).all(|w| w[0] < w[1]));
+        if !valid {
+            return Err("invalid LiuTangIter state");
+        }
+        Ok(Self { c, remaining, done })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl LiuTangIter {
+    /// Creates the iterator for the k-combinations of an n-element set.
+    ///
+    /// The iterator is empty unless `0 < k < n`.
+    pub fn new(n: usize, k: usize) -> Self {
+        let done = k == 0 || k >= n;
+        let mut c: Vec<usize> = (0..=k).map(|j| j.saturating_sub(1)).collect();
+        c.push(n);
+        Self {
+            c,
+            remaining: if done {
+                Some(0)
+            } else {
+                checked_comb(n, k).map(|r| r - 1)
+            },
+            done,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for LiuTangIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        if self.done {
+            return None;
+        }
+        let c = &mut self.c;
+        let k = c.len() - 2;
+        // the easy case moves the smallest element
+        let mut increase = k % 2 == 0;
+        if k % 2 == 1 {
+            if c[1] + 1 < c[2] {
+                c[1] += 1;
+                self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+                return Some((c[1] - 1, c[1]));
+            }
+        } else if c[1] > 0 {
+            c[1] -= 1;
+            self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+            return Some((c[1] + 1, c[1]));
+        }
+        let mut j = 2;
+        while j <= k {
+            if increase {
+                // here c[j - 1] == j - 2
+                if c[j] + 1 < c[j + 1] {
+                    c[j - 1] = c[j];
+                    c[j] += 1;
+                    self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+                    return Some((j - 2, c[j]));
+                }
+            } else if c[j] >= j {
+                // here c[j] == c[j - 1] + 1
+                let out = c[j];
+                c[j] = c[j - 1];
+                c[j - 1] = j - 2;
+                self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+                return Some((out, j - 2));
+            }
+            increase = !increase;
+            j += 1;
+        }
+        self.done = true;
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for LiuTangIter {}
+
+/// The moves driving a [`Combination`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum CombMoves {
+    Emk(EmkCombIter),
+    Chase(ChaseIter),
+    LiuTang(LiuTangIter),
+    CoolLex(MultisetIter),
+    Colex,
+}
+
+/// Stateful k-combination driven by the Eades–McKay swaps or another order
+///
+/// `Combination` owns the current k-subset of `{0, 1, ..., n-1}`, both as a 0/1
+/// vector and as a sorted list of indices, and applies each swap produced by
+/// [`EmkCombIter`] (the same swaps as `emk_comb_gen`) internally. The first combination visited is the initial
+/// configuration `1^k 0^(n-k)`, i.e. the subset `{0, 1, ..., k-1}`.
+///
+/// The other constructors visit the combinations in cool-lex order, Chase's
+/// sequence, the Liu–Tang revolving-door order or plain colex order, starting
+/// from the same subset, so the order can be chosen to suit the cost of a move:
+///
+/// | Constructor                 | Elements moved per step | Move                  |
+/// |-----------------------------|-------------------------|-----------------------|
+/// | [`Combination::new`]        | 1                       | homogeneous           |
+/// | [`Combination::chase`]      | 1                       | by at most 2 places   |
+/// | [`Combination::liu_tang`]   | 1                       | any                   |
+/// | [`Combination::cool_lex`]   | 1 or 2                  | prefix rotation       |
+/// | [`Combination::colex`]      | up to `min(k, n - k)`   | any                   |
+///
+/// Since the views borrow the state, `Combination` is advanced with
+/// [`Combination::advance`] rather than through the `Iterator` trait.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::combin::Combination;
+///
+/// let mut combs = Combination::new(3, 2);
+/// let mut seen = Vec::new();
+/// while let Some(c) = combs.advance() {
+///     assert_eq!(c.bits().iter().filter(|&&b| b).count(), 2);
+///     seen.push(c.indices().to_vec());
+/// }
+///
+/// assert_eq!(seen, [[0, 1], [0, 2], [1, 2]]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "CombinationState")
+)]
+pub struct Combination {
+    bits: Vec<bool>,
+    indices: Vec<usize>,
+    moves: CombMoves,
+    last_swaps: Vec<(usize, usize)>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct CombinationState {
+    bits: Vec<bool>,
+    indices: Vec<usize>,
+    moves: CombMoves,
+    last_swaps: Vec<(usize, usize)>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<CombinationState> for Combination {
+    type Error = &'static str;
+
+    fn try_from(state: CombinationState) -> Result<Self, Self::Error> {
+        let CombinationState {
+            bits,
+            indices,
+            moves,
+            last_swaps,
+            started,
+        } = state;
+        let (n, k) = (bits.len(), indices.len());
+        let views = (0..n).filter(|&i| bits[i]).eq(indices.iter().copied())
+            && last_swaps.iter().all(|&(i, j)| i < n && j < n);
+        // the moves must continue from the current combination
+        let moves_fit = match &moves {
+            CombMoves::Emk(iter) => {
+                iter.n == n
+                    && iter.stack.first().map_or(true, |root| root.k == k)
+                    && iter.walk.map_or(true, |(i, _)| indices == [i])
+            }
+            CombMoves::Chase(iter) => {
+                iter.a.len() == n + 1 && (iter.done || (0..n).all(|j| iter.a[j] == bits[n - 1 - j]))
+            }
+            CombMoves::LiuTang(iter) => {
+                iter.c.len() == k + 2
+                    && iter.c[k + 1] == n
+                    && (iter.done || iter.c[1..=k] == indices[..])
+            }
+            CombMoves::CoolLex(iter) => {
+                iter.len() == n
+                    && (iter.is_done()
+                        || iter.arrangement().eq(bits.iter().map(|&b| usize::from(b))))
+            }
+            CombMoves::Colex => true,
+        };
+        if !views || !moves_fit {
+            return Err("invalid Combination state");
+        }
+        Ok(Self {
+            bits,
+            indices,
+            moves,
+            last_swaps,
+            started,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Combination {
+    /// Creates the k-combinations of an n-element set in Eades–McKay order.
+    ///
+    /// Arguments:
+    ///
+    /// * `n`: The parameter `n` represents the total number of elements in the set.
+    /// * `k`: The parameter `k` represents the number of elements in each combination.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`.
+    pub fn new(n: usize, k: usize) -> Self {
+        Self::with_moves(n, k, CombMoves::Emk(EmkCombIter::new(n, k)))
+    }
+
+    /// Creates the k-combinations of an n-element set in Chase's sequence,
+    /// where each step moves one element by one or two places; see [`ChaseIter`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`.
+    pub fn chase(n: usize, k: usize) -> Self {
+        Self::with_moves(n, k, CombMoves::Chase(ChaseIter::new(n, k)))
+    }
+
+    /// Creates the k-combinations of an n-element set in the Liu–Tang
+    /// revolving-door order; see [`LiuTangIter`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`.
+    pub fn liu_tang(n: usize, k: usize) -> Self {
+        Self::with_moves(n, k, CombMoves::LiuTang(LiuTangIter::new(n, k)))
+    }
+
+    /// Creates the k-combinations of an n-element set in cool-lex order,
+    /// where each step rotates a prefix of the 0/1 vector by one place to the
+    /// right, exchanging one or two pairs of bits.
+    ///
+    /// The prefix lengths are those of [`MultisetIter`] for the multiset
+    /// `{0^(n-k), 1^k}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::combin::Combination;
+    ///
+    /// let mut combs = Combination::cool_lex(4, 2);
+    /// let mut seen = Vec::new();
+    /// while let Some(c) = combs.advance() {
+    ///     seen.push(c.indices().to_vec());
+    /// }
+    ///
+    /// assert_eq!(seen, [[0, 1], [1, 2], [0, 2], [1, 3], [2, 3], [0, 3]]);
+    /// ```
+    pub fn cool_lex(n: usize, k: usize) -> Self {
+        let ks = [n.saturating_sub(k), k];
+        Self::with_moves(n, k, CombMoves::CoolLex(MultisetIter::new(&ks)))
+    }
+
+    /// Creates the k-combinations of an n-element set in colexicographic
+    /// order, the order of [`CombOrder::Colex`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`.
+    pub fn colex(n: usize, k: usize) -> Self {
+        Self::with_moves(n, k, CombMoves::Colex)
+    }
+
+    fn with_moves(n: usize, k: usize, moves: CombMoves) -> Self {
+        assert!(k <= n, "k must not exceed n");
+        let mut bits = vec![false; n];
+        bits[..k].fill(true);
+        Self {
+            bits,
+            indices: (0..k).collect(),
+            moves,
+            last_swaps: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Moves to the next combination and returns a view of it.
+    ///
+    /// The first call returns the initial configuration `{0, 1, ..., k-1}`;
+    /// `None` is returned once all `comb(n, k)` combinations have been visited.
+    pub fn advance(&mut self) -> Option<&Self> {
+        if !self.started {
+            self.started = true;
+            return Some(self);
+        }
+        let mut swaps = core::mem::take(&mut self.last_swaps);
+        swaps.clear();
+        match &mut self.moves {
+            CombMoves::Emk(iter) => swaps.push(iter.next()?),
+            CombMoves::Chase(iter) => swaps.push(iter.next()?),
+            CombMoves::LiuTang(iter) => swaps.push(iter.next()?),
+            // the elements leaving the subset are paired with the elements
+            // entering it, both in increasing order
+            CombMoves::CoolLex(iter) => {
+                let p = iter.next()?;
+                let bits = &self.bits;
+                // bit i after rotating the prefix bits[..=p] right by one
+                let rotated = |i: usize| if i == 0 { bits[p] } else { bits[i - 1] };
+                let removed = (0..=p).filter(|&i| bits[i] && !rotated(i));
+                let added = (0..=p).filter(|&i| !bits[i] && rotated(i));
+                swaps.extend(removed.zip(added));
+            }
+            CombMoves::Colex => {
+                let c = &self.indices;
+                let k = c.len();
+                let n = self.bits.len();
+                let j = (0..k).find(|&j| c[j] + 1 < if j + 1 < k { c[j + 1] } else { n })?;
+                // c[..=j] becomes {0, 1, ..., j-1, c[j] + 1}
+                let removed = c[..=j].iter().copied().filter(|&x| x >= j);
+                let added = (0..j)
+                    .chain(Some(c[j] + 1))
+                    .filter(|x| c[..=j].binary_search(x).is_err());
+                swaps.extend(removed.zip(added));
+            }
+        }
+        for &(i, j) in &swaps {
+            self.apply_swap(i, j);
+        }
+        self.last_swaps = swaps;
+        Some(self)
+    }
+
+    fn apply_swap(&mut self, i: usize, j: usize) {
+        self.bits.swap(i, j);
+        let (removed, added) = if self.bits[i] { (j, i) } else { (i, j) };
+        if let Ok(pos) = self.indices.binary_search(&removed) {
+            self.indices.remove(pos);
+        }
+        if let Err(pos) = self.indices.binary_search(&added) {
+            self.indices.insert(pos, added);
+        }
+    }
+
+    /// The current combination as a 0/1 vector of length `n`.
+    #[inline]
+    pub fn bits(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// The current combination as a sorted list of `k` indices.
+    #[inline]
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// The swap that produced the current combination, or `None` for the
+    /// initial configuration or if the last step exchanged more than one
+    /// pair of bits (see [`Combination::last_swaps`]).
+    #[inline]
+    pub fn last_swap(&self) -> Option<(usize, usize)> {
+        match self.last_swaps[..] {
+            [swap] => Some(swap),
+            _ => None,
+        }
+    }
+
+    /// The swaps that produced the current combination from the previous one,
+    /// applied in order; empty for the initial configuration.
+    #[inline]
+    pub fn last_swaps(&self) -> &[(usize, usize)] {
+        &self.last_swaps
+    }
+}
+
+/// Orders for ranking and unranking k-combinations
+///
+/// Combinations are given as sorted lists of indices `c[0] < c[1] < ... < c[k-1]`
+/// taken from `{0, 1, ..., n-1}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CombOrder {
+    /// Lexicographic order of the sorted index lists, e.g. `01, 02, 03, 12, 13, 23`.
+    Lex,
+    /// Colexicographic order, i.e. ordered by the largest index first, e.g.
+    /// `01, 02, 12, 03, 13, 23`.
+    Colex,
+    /// The Eades–McKay revolving-door order produced by [`emk_comb_gen`] and
+    /// [`Combination`], e.g. `01, 02, 12, 13, 03, 23`.
+    Emk,
+}
+
+/// The band of Pascal's triangle with `table[m][j] = C(m, j)` for `m <= n`,
+/// `j <= k` and `m - j <= n - k`, where `k <= n`.
+///
+/// These are the only entries the ranks look up, and none exceeds `C(n, k)`;
+/// the others are zero.
+#[cfg(feature = "alloc")]
+fn binom_table(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut table = vec![vec![0usize; k + 1]; n + 1];
+    for m in 0..=n {
+        for j in m.saturating_sub(n - k)..=k.min(m) {
+            table[m][j] = if j == 0 {
+                1
+            } else {
+                table[m - 1][j - 1] + table[m - 1][j]
+            };
+        }
+    }
+    table
+}
+
+/// Colex rank of a sorted combination: the sum of `C(c[i], i + 1)`.
+#[cfg(feature = "alloc")]
+fn rank_colex(c: &[usize], table: &[Vec<usize>]) -> usize {
+    c.iter().enumerate().map(|(i, &x)| table[x][i + 1]).sum()
+}
+
+/// Inverse of [`rank_colex`], picking the largest `x` with `C(x, i + 1) <= r`
+/// from the top index downwards.
+#[cfg(feature = "alloc")]
+fn unrank_colex(mut r: usize, n: usize, k: usize, table: &[Vec<usize>]) -> Vec<usize> {
+    let mut c = vec![0; k];
+    let mut x = n;
+    for i in (0..k).rev() {
+        x -= 1;
+        while table[x][i + 1] > r {
+            x -= 1;
+        }
+        r -= table[x][i + 1];
+        c[i] = x;
+    }
+    c
+}
+
+/// Rank of a 0/1 vector in the Eades–McKay order.
+///
+/// With the last position as the most significant, the list `E(n, k)` visited by
+/// [`emk_comb_gen`] is
+///
+/// ```text
+/// E(n, k) = E(n-1, k).0,  reverse(E(n-2, k-1)).01,  E(n-2, k-2).11
+/// ```
+///
+/// and `E(n, 0)`, `E(n, n)` consist of a single string. This is the same split
+/// as in [`emk_gen_even`] and [`emk_gen_odd`], whose reversed halves are
+/// `emk_neg_odd` and `emk_neg_even`. The recursion is unrolled by keeping track
+/// of whether the current sublist is reversed.
+#[cfg(feature = "alloc")]
+fn rank_emk(bits: &[bool], k: usize, table: &[Vec<usize>]) -> usize {
+    let (mut m, mut j) = (bits.len(), k);
+    let mut rank = 0;
+    let mut reversed = false;
+    while j != 0 && j != m {
+        if !bits[m - 1] {
+            m -= 1;
+            continue;
+        }
+        let skip = table[m - 1][j] + table[m - 2][j - 1];
+        let (offset, flip) = if bits[m - 2] {
+            j -= 2;
+            (skip, false)
+        } else {
+            j -= 1;
+            (skip - 1, true)
+        };
+        m -= 2;
+        if reversed {
+            rank -= offset;
+        } else {
+            rank += offset;
+        }
+        reversed ^= flip;
+    }
+    rank
+}
+
+/// Inverse of [`rank_emk`].
+#[cfg(feature = "alloc")]
+fn unrank_emk(mut r: usize, n: usize, k: usize, table: &[Vec<usize>]) -> Vec<bool> {
+    let mut bits = vec![false; n];
+    let (mut m, mut j) = (n, k);
+    while j != 0 && j != m {
+        let c1 = table[m - 1][j];
+        let c2 = table[m - 2][j - 1];
+        if r < c1 {
+            m -= 1;
+        } else if r < c1 + c2 {
+            bits[m - 1] = true;
+            r = c2 - 1 - (r - c1);
+            m -= 2;
+            j -= 1;
+        } else {
+            bits[m - 1] = true;
+            bits[m - 2] = true;
+            r -= c1 + c2;
+            m -= 2;
+            j -= 2;
+        }
+    }
+    bits[..j].fill(true);
+    bits
+}
+
+/// Rank of a k-combination in lex, colex or Eades–McKay order
+///
+/// The `rank_comb` function returns the position (starting from 0) of the combination `c` in the
+/// list of all k-combinations of `{0, 1, ..., n-1}` in the given order. The ranks range over
+/// `0..comb(n, k)`, so a search space can be split into ranges and resumed with [`unrank_comb`].
+///
+/// Arguments:
+///
+/// * `c`: The combination as a strictly increasing list of indices, each less than `n`.
+/// * `n`: The parameter `n` represents the total number of elements in the set.
+/// * `order`: The order in which the combinations are listed.
+///
+/// Returns:
+///
+/// The function `rank_comb` returns the rank of `c` as a `usize`.
+///
+/// # Panics
+///
+/// Panics if `c` has more than `n` indices or `comb(n, k)` does not fit in a
+/// `usize`, where `k` is the length of `c`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::combin::{rank_comb, CombOrder};
+///
+/// assert_eq!(rank_comb(&[0, 1], 4, CombOrder::Lex), 0);
+/// assert_eq!(rank_comb(&[0, 3], 4, CombOrder::Lex), 2);
+/// assert_eq!(rank_comb(&[0, 3], 4, CombOrder::Colex), 3);
+/// assert_eq!(rank_comb(&[2, 3], 4, CombOrder::Colex), 5);
+/// assert_eq!(rank_comb(&[0, 3], 4, CombOrder::Emk), 4);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn rank_comb(c: &[usize], n: usize, order: CombOrder) -> usize {
+    debug_assert!(c.windows(2).all(|w| w[0] < w[1]));
+    debug_assert!(c.iter().all(|&x| x < n));
+    let k = c.len();
+    assert!(k <= n, "k must not exceed n");
+    assert!(
+        checked_comb(n, k).is_some(),
+        "rank_comb: comb(n, k) overflows usize"
+    );
+    let table = binom_table(n, k);
+    match order {
+        CombOrder::Colex => rank_colex(c, &table),
+        CombOrder::Lex => {
+            // Lex order is the reverse of the colex order of the mirrored set.
+            let mirror: Vec<usize> = c.iter().rev().map(|&x| n - 1 - x).collect();
+            table[n][k] - 1 - rank_colex(&mirror, &table)
+        }
+        CombOrder::Emk => {
+            let mut bits = vec![false; n];
+            for &x in c {
+                bits[x] = true;
+            }
+            rank_emk(&bits, k, &table)
+        }
+    }
+}
+
+/// The k-combination of a given rank in lex, colex or Eades–McKay order
+///
+/// The `unrank_comb` function is the inverse of [`rank_comb`].
+///
+/// Arguments:
+///
+/// * `r`: The rank of the combination, less than `comb(n, k)`.
+/// * `n`: The parameter `n` represents the total number of elements in the set.
+/// * `k`: The parameter `k` represents the number of elements in the combination.
+/// * `order`: The order in which the combinations are listed.
+///
+/// Returns:
+///
+/// The function `unrank_comb` returns the combination as a sorted `Vec` of `k` indices.
+///
+/// # Panics
+///
+/// Panics if `k > n`, if `comb(n, k)` does not fit in a `usize`, or if `r` is
+/// not a valid rank.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::combin::{unrank_comb, CombOrder};
+///
+/// assert_eq!(unrank_comb(2, 4, 2, CombOrder::Lex), [0, 3]);
+/// assert_eq!(unrank_comb(2, 4, 2, CombOrder::Colex), [1, 2]);
+/// assert_eq!(unrank_comb(3, 4, 2, CombOrder::Emk), [1, 3]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn unrank_comb(r: usize, n: usize, k: usize, order: CombOrder) -> Vec<usize> {
+    assert!(k <= n, "k must not exceed n");
+    assert!(
+        checked_comb(n, k).is_some(),
+        "unrank_comb: comb(n, k) overflows usize"
+    );
+    let table = binom_table(n, k);
+    assert!(r < table[n][k], "rank out of range");
+    match order {
+        CombOrder::Colex => unrank_colex(r, n, k, &table),
+        CombOrder::Lex => unrank_colex(table[n][k] - 1 - r, n, k, &table)
+            .iter()
+            .rev()
+            .map(|&x| n - 1 - x)
+            .collect(),
+        CombOrder::Emk => {
+            let bits = unrank_emk(r, n, k, &table);
+            (0..n).filter(|&i| bits[i]).collect()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,6 +1745,42 @@ mod tests {
         assert_eq!(comb(3, 2), 3);
     }
 
+    #[test]
+    fn test_checked_comb() {
+        // Pascal's triangle in u128 up to the point where it overflows
+        let mut row = [0u128; 132];
+        row[0] = 1;
+        for n in 0..=130u128 {
+            for k in 0..=n + 1 {
+                assert_eq!(checked_comb_u128(n, k), Some(row[k as usize]));
+            }
+            for k in (1..=n as usize + 1).rev() {
+                row[k] += row[k - 1];
+            }
+        }
+        assert_eq!(checked_comb_u128(132, 66), None);
+        assert_eq!(checked_comb_u128(1 << 100, 1), Some(1 << 100));
+        assert_eq!(checked_comb_u128(1 << 100, 2), None);
+        assert_eq!(checked_comb(67, 33), Some(14_226_520_737_620_288_370));
+        assert_eq!(checked_comb(6, 7), Some(0));
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(checked_comb(68, 34), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_comb_big() {
+        for n in 0..80 {
+            for k in 0..=n + 1 {
+                if let Some(r) = checked_comb_u128(n as u128, k as u128) {
+                    assert_eq!(comb_big(n, k), num_bigint::BigUint::from(r));
+                }
+            }
+        }
+        assert_eq!(comb_big(200, 100), comb_big(199, 99) * 2u32);
+    }
+
+    #[cfg(feature = "generator")]
     #[test]
     fn test_emk_even_odd() {
         let mut cnt = 1;
@@ -361,6 +1790,7 @@ mod tests {
         assert_eq!(cnt, comb(16, 5));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_emk_odd_odd() {
         let mut cnt = 1;
@@ -370,6 +1800,7 @@ mod tests {
         assert_eq!(cnt, comb(15, 5));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_emk_even_even() {
         let mut cnt = 1;
@@ -379,6 +1810,7 @@ mod tests {
         assert_eq!(cnt, comb(16, 6));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_emk_odd_even() {
         let mut cnt = 1;
@@ -387,4 +1819,205 @@ mod tests {
         }
         assert_eq!(cnt, comb(15, 6));
     }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_combination_visits_all() {
+        for (n, k) in [(5, 0), (5, 1), (5, 5), (6, 3), (7, 4), (8, 2)] {
+            let mut combs = Combination::new(n, k);
+            let mut seen = Vec::new();
+            while let Some(c) = combs.advance() {
+                let from_bits: Vec<usize> = (0..n).filter(|&i| c.bits()[i]).collect();
+                assert_eq!(from_bits, c.indices());
+                seen.push(c.indices().to_vec());
+            }
+            let cnt = seen.len();
+            seen.sort();
+            seen.dedup();
+            assert_eq!(seen.len(), cnt);
+            assert_eq!(cnt, comb(n, k));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_combination_last_swap() {
+        let mut combs = Combination::new(4, 2);
+        assert_eq!(combs.advance().unwrap().last_swap(), None);
+        assert!(combs.advance().unwrap().last_swap().is_some());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rank_comb_lex_colex() {
+        let n = 7;
+        let k = 3;
+        let mut all = Vec::new();
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    all.push(vec![a, b, c]);
+                }
+            }
+        }
+        for (r, c) in all.iter().enumerate() {
+            assert_eq!(rank_comb(c, n, CombOrder::Lex), r);
+            assert_eq!(unrank_comb(r, n, k, CombOrder::Lex), *c);
+        }
+        all.sort_by(|x, y| x.iter().rev().cmp(y.iter().rev()));
+        for (r, c) in all.iter().enumerate() {
+            assert_eq!(rank_comb(c, n, CombOrder::Colex), r);
+            assert_eq!(unrank_comb(r, n, k, CombOrder::Colex), *c);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rank_comb_edge_cases() {
+        assert_eq!(rank_comb(&[], 5, CombOrder::Lex), 0);
+        assert_eq!(unrank_comb(0, 5, 0, CombOrder::Colex), Vec::<usize>::new());
+        assert_eq!(unrank_comb(0, 4, 4, CombOrder::Lex), [0, 1, 2, 3]);
+        let r = comb(40, 8) - 1;
+        assert_eq!(
+            unrank_comb(r, 40, 8, CombOrder::Lex),
+            [32, 33, 34, 35, 36, 37, 38, 39]
+        );
+        assert_eq!(
+            rank_comb(&[32, 33, 34, 35, 36, 37, 38, 39], 40, CombOrder::Colex),
+            r
+        );
+        // C(100, 98) is small although C(100, 50) overflows
+        for order in [CombOrder::Lex, CombOrder::Colex, CombOrder::Emk] {
+            for r in [0, 1, 4948, 4949] {
+                let c = unrank_comb(r, 100, 98, order);
+                assert_eq!(rank_comb(&c, 100, order), r);
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rank_comb_emk() {
+        for n in 1..10 {
+            for k in 0..=n {
+                let mut combs = Combination::new(n, k);
+                let mut r = 0;
+                while let Some(c) = combs.advance() {
+                    assert_eq!(rank_comb(c.indices(), n, CombOrder::Emk), r);
+                    assert_eq!(unrank_comb(r, n, k, CombOrder::Emk), c.indices());
+                    r += 1;
+                }
+                assert_eq!(r, comb(n, k));
+            }
+        }
+    }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_emk_iter_matches_generator() {
+        for n in 1..13 {
+            for k in 0..=n {
+                assert!(
+                    EmkCombIter::new(n, k).eq(emk_comb_gen(n, k)),
+                    "n={n}, k={k}"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_emk_iter_size_hint() {
+        for (n, k) in [(16, 5), (15, 6), (5, 1), (5, 0), (5, 5)] {
+            let iter = EmkCombIter::new(n, k);
+            let (lo, hi) = iter.size_hint();
+            assert_eq!(Some(lo), hi);
+            assert_eq!(iter.count(), lo);
+            assert_eq!(lo, comb(n, k) - 1);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_combination_alternative_orders() {
+        type Ctor = fn(usize, usize) -> Combination;
+        let orders: [(Ctor, usize); 5] = [
+            (Combination::new, 1),
+            (Combination::chase, 1),
+            (Combination::liu_tang, 1),
+            (Combination::cool_lex, 2),
+            (Combination::colex, usize::MAX),
+        ];
+        for n in 0..10 {
+            for k in 0..=n {
+                for (ctor, max_swaps) in orders {
+                    let mut combs = ctor(n, k);
+                    let mut seen = Vec::new();
+                    let mut prev: Vec<usize> = (0..k).collect();
+                    let mut bits: Vec<bool> = (0..n).map(|i| i < k).collect();
+                    while let Some(c) = combs.advance() {
+                        let from_bits: Vec<usize> = (0..n).filter(|&i| c.bits()[i]).collect();
+                        assert_eq!(from_bits, c.indices());
+                        let swaps = c.last_swaps();
+                        assert!(swaps.len() <= max_swaps);
+                        assert_eq!(swaps.is_empty(), seen.is_empty());
+                        let changed = c.indices().iter().filter(|x| !prev.contains(x)).count();
+                        assert_eq!(changed, swaps.len());
+                        for &(i, j) in swaps {
+                            bits.swap(i, j);
+                        }
+                        assert_eq!(bits, c.bits());
+                        prev = c.indices().to_vec();
+                        seen.push(prev.clone());
+                    }
+                    let cnt = seen.len();
+                    seen.sort();
+                    seen.dedup();
+                    assert_eq!(seen.len(), cnt);
+                    assert_eq!(cnt, comb(n, k));
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_chase_moves_at_most_two_places() {
+        for n in 1..12 {
+            for k in 1..n {
+                let mut bits: Vec<bool> = (0..n).map(|i| i < k).collect();
+                for (i, j) in ChaseIter::new(n, k) {
+                    assert!(bits[i] && !bits[j]);
+                    assert!(i.abs_diff(j) <= 2);
+                    bits.swap(i, j);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_colex_matches_rank() {
+        let (n, k) = (7, 3);
+        let mut combs = Combination::colex(n, k);
+        let mut r = 0;
+        while let Some(c) = combs.advance() {
+            assert_eq!(rank_comb(c.indices(), n, CombOrder::Colex), r);
+            r += 1;
+        }
+        assert_eq!(r, comb(n, k));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_chase_liu_tang_size_hint() {
+        for (n, k) in [(16, 5), (15, 6), (5, 1), (5, 0), (5, 5)] {
+            let chase = ChaseIter::new(n, k);
+            assert_eq!(chase.size_hint(), (comb(n, k) - 1, Some(comb(n, k) - 1)));
+            assert_eq!(chase.count(), comb(n, k) - 1);
+            let liu_tang = LiuTangIter::new(n, k);
+            assert_eq!(liu_tang.size_hint(), (comb(n, k) - 1, Some(comb(n, k) - 1)));
+            assert_eq!(liu_tang.count(), comb(n, k) - 1);
+        }
+    }
 }
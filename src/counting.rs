@@ -0,0 +1,1075 @@
+//! Counting functions
+//!
+//! This module collects the companion counts of the generators in this crate,
+//! so that the length of an enumeration can be checked before starting it.
+//!
+//! ## Key Functions
+//!
+//! - [`stirling1st`] / [`stirling1st_signed`] - Stirling numbers of the first kind,
+//!   counting permutations of n elements with k cycles
+//! - [`bell`] - Bell numbers, counting all partitions of an n-set
+//! - [`lah`] - Lah numbers, counting partitions of an n-set into k ordered lists
+//! - [`catalan`] - Catalan numbers
+//! - [`multinomial`] - Multinomial coefficients, counting arrangements of a multiset
+//! - [`derangement`] / [`involution`] - Permutations without fixed points and
+//!   permutations that are their own inverse
+//!
+//! Every function has a `checked_*` variant returning `None` instead of
+//! overflowing. With the `alloc` feature, `stirling1st_table`,
+//! `stirling1st_signed_table`, `stirling2nd_table`, `lah_table`, `bell_table`,
+//! `catalan_table`, `derangement_table` and `involution_table` return all
+//! values up to `n` at once; there is no table of multinomial coefficients.
+//!
+//! ## Complexity
+//!
+//! - `stirling1st(n, k)`: O(min(k, n-k) (n-k)) time, O(1) space
+//! - `bell(n)`: O(n^3) time, O(1) space
+//! - `lah(n, k)`, `catalan(n)`, `derangement(n)`, `involution(n)`: O(n) time, O(1) space
+//! - `multinomial(ks)`: O(sum(ks)) time, O(1) space
+//! - `*_table(n)`: O(n^2) time and space
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::combin::checked_comb_u128;
+use crate::set_partition::{checked_stirling2nd_u128, checked_stirling_eulerian_u128};
+
+/// Narrows a `u128` count to `usize`.
+#[inline]
+const fn to_usize(r: Option<u128>) -> Option<usize> {
+    match r {
+        Some(r) if r <= usize::MAX as u128 => Some(r as usize),
+        _ => None,
+    }
+}
+
+/// The `stirling1st` function calculates the unsigned Stirling number of the first kind c(n, k).
+///
+/// Arguments:
+///
+/// * `n`: The number of elements being permuted.
+/// * `k`: The number of cycles.
+///
+/// Returns:
+///
+/// The number of permutations of `n` elements with exactly `k` cycles.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::stirling1st;
+///
+/// assert_eq!(stirling1st(5, 2), 50);
+/// assert_eq!(stirling1st(4, 4), 1);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_stirling1st`].
+pub const fn stirling1st(n: usize, k: usize) -> usize {
+    match checked_stirling1st(n, k) {
+        Some(r) => r,
+        None => panic!("stirling1st: result overflows usize"),
+    }
+}
+
+/// The `checked_stirling1st` function calculates the unsigned Stirling number of the first kind
+/// c(n, k), returning `None` instead of overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements being permuted.
+/// * `k`: The number of cycles.
+///
+/// Returns:
+///
+/// `Some(c(n, k))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_stirling1st;
+///
+/// assert_eq!(checked_stirling1st(5, 2), Some(50));
+/// assert_eq!(checked_stirling1st(3, 5), Some(0));
+/// assert_eq!(checked_stirling1st(40, 1), None);
+/// ```
+pub const fn checked_stirling1st(n: usize, k: usize) -> Option<usize> {
+    to_usize(checked_stirling1st_u128(n as u128, k as u128))
+}
+
+/// c(n, k) in `u128`, computed in the same way as `checked_stirling2nd_u128`: the table holds
+/// c(i + j, i) for j <= n - k when k <= n - k, and the second-order Eulerian numbers give the
+/// value otherwise. No intermediate value exceeds the result.
+const fn checked_stirling1st_u128(n: u128, k: u128) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    if k == n {
+        return Some(1);
+    }
+    if k == 0 {
+        return Some(0);
+    }
+    let m = n - k;
+    if m == 1 {
+        return checked_comb_u128(n, 2);
+    }
+    // c(n, k) >= S(n, k) >= 2^m for k >= 2
+    if m >= 128 {
+        return None;
+    }
+    if k > m {
+        return checked_stirling_eulerian_u128(n, m as usize, true);
+    }
+    let m = m as usize;
+    // row[j] = c(i + j, i) for the current i
+    let mut row = [0u128; 128];
+    row[0] = 1;
+    let mut i: u128 = 1;
+    while i <= k {
+        let mut j = 1;
+        while j <= m {
+            let factor = i + j as u128 - 1;
+            row[j] = match row[j - 1].checked_mul(factor) {
+                Some(v) => match v.checked_add(row[j]) {
+                    Some(v) => v,
+                    None => return None,
+                },
+                None => return None,
+            };
+            j += 1;
+        }
+        i += 1;
+    }
+    Some(row[m])
+}
+
+/// The `stirling1st_signed` function calculates the signed Stirling number of the first kind
+/// s(n, k) = (-1)^(n-k) c(n, k).
+///
+/// Arguments:
+///
+/// * `n`: The number of elements being permuted.
+/// * `k`: The number of cycles.
+///
+/// Returns:
+///
+/// The coefficient of x^k in the falling factorial x(x-1)...(x-n+1).
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::stirling1st_signed;
+///
+/// assert_eq!(stirling1st_signed(5, 2), -50);
+/// assert_eq!(stirling1st_signed(5, 3), 35);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in an `isize`; see [`checked_stirling1st_signed`].
+pub const fn stirling1st_signed(n: usize, k: usize) -> isize {
+    match checked_stirling1st_signed(n, k) {
+        Some(r) => r,
+        None => panic!("stirling1st_signed: result overflows isize"),
+    }
+}
+
+/// The `checked_stirling1st_signed` function calculates the signed Stirling number of the first
+/// kind s(n, k), returning `None` instead of overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements being permuted.
+/// * `k`: The number of cycles.
+///
+/// Returns:
+///
+/// `Some(s(n, k))`, or `None` if it does not fit in an `isize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_stirling1st_signed;
+///
+/// assert_eq!(checked_stirling1st_signed(4, 1), Some(-6));
+/// assert_eq!(checked_stirling1st_signed(40, 1), None);
+/// ```
+pub const fn checked_stirling1st_signed(n: usize, k: usize) -> Option<isize> {
+    match checked_stirling1st_u128(n as u128, k as u128) {
+        Some(r) if r <= isize::MAX as u128 => {
+            if n >= k && (n - k) % 2 == 1 {
+                Some(-(r as isize))
+            } else {
+                Some(r as isize)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The `bell` function calculates the Bell number B(n).
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// The number of partitions of an `n`-element set into any number of blocks.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::bell;
+///
+/// assert_eq!(bell(0), 1);
+/// assert_eq!(bell(5), 52);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_bell`].
+pub const fn bell(n: usize) -> usize {
+    match checked_bell(n) {
+        Some(r) => r,
+        None => panic!("bell: result overflows usize"),
+    }
+}
+
+/// The `checked_bell` function calculates the Bell number B(n), returning `None` instead of
+/// overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// `Some(B(n))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_bell;
+///
+/// assert_eq!(checked_bell(10), Some(115_975));
+/// assert_eq!(checked_bell(30), None);
+/// ```
+pub const fn checked_bell(n: usize) -> Option<usize> {
+    to_usize(checked_bell_u128(n as u128))
+}
+
+/// The `checked_bell_u128` function calculates the Bell number B(n) in `u128`, returning
+/// `None` instead of overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// `Some(B(n))`, or `None` if it does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_bell_u128;
+///
+/// assert_eq!(checked_bell_u128(30), Some(846_749_014_511_809_332_450_147));
+/// assert_eq!(checked_bell_u128(60), None);
+/// ```
+pub const fn checked_bell_u128(n: u128) -> Option<u128> {
+    // B(n) >= S(n, 2) = 2^(n-1) - 1
+    if n > 129 {
+        return None;
+    }
+    let mut total: u128 = 0;
+    let mut k = 0;
+    while k <= n {
+        total = match checked_stirling2nd_u128(n, k) {
+            Some(s) => match total.checked_add(s) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        k += 1;
+    }
+    Some(total)
+}
+
+/// The `lah` function calculates the unsigned Lah number L(n, k).
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+/// * `k`: The number of lists.
+///
+/// Returns:
+///
+/// The number of ways to partition an `n`-element set into `k` non-empty linearly ordered
+/// lists.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::lah;
+///
+/// assert_eq!(lah(4, 2), 36);
+/// assert_eq!(lah(3, 1), 6);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_lah`].
+pub const fn lah(n: usize, k: usize) -> usize {
+    match checked_lah(n, k) {
+        Some(r) => r,
+        None => panic!("lah: result overflows usize"),
+    }
+}
+
+/// The `checked_lah` function calculates the unsigned Lah number L(n, k) = C(n-1, k-1) n!/k!,
+/// returning `None` instead of overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+/// * `k`: The number of lists.
+///
+/// Returns:
+///
+/// `Some(L(n, k))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_lah;
+///
+/// assert_eq!(checked_lah(4, 2), Some(36));
+/// assert_eq!(checked_lah(0, 0), Some(1));
+/// assert_eq!(checked_lah(4, 0), Some(0));
+/// ```
+pub const fn checked_lah(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+    if k == 0 {
+        return Some(if n == 0 { 1 } else { 0 });
+    }
+    let mut r = match checked_comb_u128(n as u128 - 1, k as u128 - 1) {
+        Some(r) => r,
+        None => return None,
+    };
+    let mut i = k as u128 + 1;
+    while i <= n as u128 {
+        r = match r.checked_mul(i) {
+            Some(v) => v,
+            None => return None,
+        };
+        i += 1;
+    }
+    to_usize(Some(r))
+}
+
+/// The `catalan` function calculates the Catalan number C_n = C(2n, n) / (n + 1).
+///
+/// Arguments:
+///
+/// * `n`: The index of the Catalan number.
+///
+/// Returns:
+///
+/// The n-th Catalan number, e.g. the number of binary trees with `n` internal nodes.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::catalan;
+///
+/// assert_eq!(catalan(0), 1);
+/// assert_eq!(catalan(5), 42);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_catalan`].
+pub const fn catalan(n: usize) -> usize {
+    match checked_catalan(n) {
+        Some(r) => r,
+        None => panic!("catalan: result overflows usize"),
+    }
+}
+
+/// The `checked_catalan` function calculates the Catalan number C_n, returning `None` instead
+/// of overflowing.
+///
+/// The recurrence C_(i+1) = C_i * 2(2i + 1) / (i + 2) is applied with the common factor
+/// cancelled first, so `None` is returned only when C_n itself overflows.
+///
+/// Arguments:
+///
+/// * `n`: The index of the Catalan number.
+///
+/// Returns:
+///
+/// `Some(C_n)`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_catalan;
+///
+/// assert_eq!(checked_catalan(10), Some(16_796));
+/// assert_eq!(checked_catalan(100), None);
+/// ```
+pub const fn checked_catalan(n: usize) -> Option<usize> {
+    let mut r: u128 = 1;
+    let mut i: u128 = 0;
+    while i < n as u128 {
+        let mut a = r;
+        let mut b = i + 2;
+        while b != 0 {
+            let t = a % b;
+            a = b;
+            b = t;
+        }
+        let g = a;
+        r = match (r / g).checked_mul(2 * (2 * i + 1) / ((i + 2) / g)) {
+            Some(v) if v <= usize::MAX as u128 => v,
+            _ => return None,
+        };
+        i += 1;
+    }
+    Some(r as usize)
+}
+
+/// The `multinomial` function calculates the multinomial coefficient (k1 + ... + km)! / (k1! ... km!).
+///
+/// Arguments:
+///
+/// * `ks`: The multiplicities of the distinct elements of a multiset.
+///
+/// Returns:
+///
+/// The number of distinct arrangements of a multiset with the given multiplicities.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::{comb, multinomial};
+///
+/// assert_eq!(multinomial(&[2, 1, 3]), 60);
+/// assert_eq!(multinomial(&[3, 4]), comb(7, 3));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_multinomial`].
+pub const fn multinomial(ks: &[usize]) -> usize {
+    match checked_multinomial(ks) {
+        Some(r) => r,
+        None => panic!("multinomial: result overflows usize"),
+    }
+}
+
+/// The `checked_multinomial` function calculates the multinomial coefficient, returning `None`
+/// instead of overflowing.
+///
+/// The coefficient is the product of C(k1 + ... + ki, ki), whose partial products never exceed
+/// the result.
+///
+/// Arguments:
+///
+/// * `ks`: The multiplicities of the distinct elements of a multiset.
+///
+/// Returns:
+///
+/// `Some(coefficient)`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_multinomial;
+///
+/// assert_eq!(checked_multinomial(&[]), Some(1));
+/// assert_eq!(checked_multinomial(&[1; 30]), None);
+/// ```
+pub const fn checked_multinomial(ks: &[usize]) -> Option<usize> {
+    let mut r: u128 = 1;
+    let mut total: u128 = 0;
+    let mut i = 0;
+    while i < ks.len() {
+        total += ks[i] as u128;
+        r = match checked_comb_u128(total, ks[i] as u128) {
+            Some(c) => match r.checked_mul(c) {
+                Some(v) if v <= usize::MAX as u128 => v,
+                _ => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(r as usize)
+}
+
+/// The `derangement` function calculates the number of derangements D(n), the permutations of
+/// n elements without fixed points.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+///
+/// Returns:
+///
+/// The derangement number D(n), i.e. the "subfactorial" !n.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::derangement;
+///
+/// assert_eq!(derangement(0), 1);
+/// assert_eq!(derangement(1), 0);
+/// assert_eq!(derangement(5), 44);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_derangement`].
+pub const fn derangement(n: usize) -> usize {
+    match checked_derangement(n) {
+        Some(r) => r,
+        None => panic!("derangement: result overflows usize"),
+    }
+}
+
+/// The `checked_derangement` function calculates the derangement number D(n), returning
+/// `None` instead of overflowing.
+///
+/// The recurrence D(i) = (i - 1)(D(i - 1) + D(i - 2)) is increasing from i = 2 on, so `None`
+/// is returned only when D(n) itself overflows.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+///
+/// Returns:
+///
+/// `Some(D(n))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_derangement;
+///
+/// assert_eq!(checked_derangement(10), Some(1_334_961));
+/// assert_eq!(checked_derangement(30), None);
+/// ```
+pub const fn checked_derangement(n: usize) -> Option<usize> {
+    let (mut prev, mut cur) = (1usize, 0usize);
+    if n == 0 {
+        return Some(1);
+    }
+    let mut i = 2;
+    while i <= n {
+        let next = match prev.checked_add(cur) {
+            Some(v) => match (i - 1).checked_mul(v) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        prev = cur;
+        cur = next;
+        i += 1;
+    }
+    Some(cur)
+}
+
+/// The `involution` function calculates the number of involutions I(n), the permutations of
+/// n elements that are their own inverse.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+///
+/// Returns:
+///
+/// The involution number I(n), which also counts the matchings of the complete graph K_n.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::involution;
+///
+/// assert_eq!(involution(0), 1);
+/// assert_eq!(involution(4), 10);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; see [`checked_involution`].
+pub const fn involution(n: usize) -> usize {
+    match checked_involution(n) {
+        Some(r) => r,
+        None => panic!("involution: result overflows usize"),
+    }
+}
+
+/// The `checked_involution` function calculates the involution number I(n), returning `None`
+/// instead of overflowing.
+///
+/// The recurrence I(i) = I(i - 1) + (i - 1) I(i - 2) is increasing, so `None` is returned only
+/// when I(n) itself overflows.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+///
+/// Returns:
+///
+/// `Some(I(n))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_involution;
+///
+/// assert_eq!(checked_involution(10), Some(9_496));
+/// assert_eq!(checked_involution(60), None);
+/// ```
+pub const fn checked_involution(n: usize) -> Option<usize> {
+    let (mut prev, mut cur) = (1usize, 1usize);
+    let mut i = 2;
+    while i <= n {
+        let next = match (i - 1).checked_mul(prev) {
+            Some(v) => match v.checked_add(cur) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        prev = cur;
+        cur = next;
+        i += 1;
+    }
+    Some(cur)
+}
+
+/// Builds the triangle `t[i][j]` for `0 <= j <= i <= n` from `t[0][0] = 1` and
+/// `t[i + 1][j] = a(i, j) * t[i][j] + t[i][j - 1]`, or `None` on overflow.
+#[cfg(feature = "alloc")]
+fn triangle(n: usize, a: impl Fn(usize, usize) -> usize) -> Option<Vec<Vec<usize>>> {
+    let mut rows: Vec<Vec<usize>> = Vec::with_capacity(n + 1);
+    rows.push(vec![1]);
+    for i in 0..n {
+        let prev = &rows[i];
+        let mut row = vec![0; i + 2];
+        for (j, item) in row.iter_mut().enumerate() {
+            let keep = match prev.get(j) {
+                Some(&t) => a(i, j).checked_mul(t)?,
+                None => 0,
+            };
+            let shift = if j > 0 { prev[j - 1] } else { 0 };
+            *item = keep.checked_add(shift)?;
+        }
+        rows.push(row);
+    }
+    Some(rows)
+}
+
+/// The `stirling1st_table` function returns the unsigned Stirling numbers of the first kind
+/// c(i, j) for all `0 <= j <= i <= n`.
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// The rows `0..=n` of the triangle (row `i` has `i + 1` entries), or `None` if any entry
+/// overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::stirling1st_table;
+///
+/// let t = stirling1st_table(4).unwrap();
+/// assert_eq!(t[4], [0, 6, 11, 6, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn stirling1st_table(n: usize) -> Option<Vec<Vec<usize>>> {
+    triangle(n, |i, _| i)
+}
+
+/// The `stirling1st_signed_table` function returns the signed Stirling numbers of the first
+/// kind s(i, j) for all `0 <= j <= i <= n`.
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// The rows `0..=n` of the triangle, or `None` if any entry overflows `isize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::stirling1st_signed_table;
+///
+/// let t = stirling1st_signed_table(4).unwrap();
+/// assert_eq!(t[4], [0, -6, 11, -6, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn stirling1st_signed_table(n: usize) -> Option<Vec<Vec<isize>>> {
+    let rows = stirling1st_table(n)?;
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.into_iter()
+                .enumerate()
+                .map(|(j, c)| {
+                    let c = isize::try_from(c).ok()?;
+                    Some(if (i - j) % 2 == 1 { -c } else { c })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The `stirling2nd_table` function returns the Stirling numbers of the second kind S(i, j)
+/// for all `0 <= j <= i <= n`.
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// The rows `0..=n` of the triangle, or `None` if any entry overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::stirling2nd_table;
+///
+/// let t = stirling2nd_table(4).unwrap();
+/// assert_eq!(t[4], [0, 1, 7, 6, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn stirling2nd_table(n: usize) -> Option<Vec<Vec<usize>>> {
+    triangle(n, |_, j| j)
+}
+
+/// The `lah_table` function returns the unsigned Lah numbers L(i, j) for all
+/// `0 <= j <= i <= n`.
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// The rows `0..=n` of the triangle, or `None` if any entry overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::lah_table;
+///
+/// let t = lah_table(4).unwrap();
+/// assert_eq!(t[4], [0, 24, 36, 12, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn lah_table(n: usize) -> Option<Vec<Vec<usize>>> {
+    triangle(n, |i, j| if j == 0 { 0 } else { i + j })
+}
+
+/// The `bell_table` function returns the Bell numbers B(0), ..., B(n), computed with the Bell
+/// triangle.
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// `n + 1` Bell numbers, or `None` if any of them overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::bell_table;
+///
+/// assert_eq!(bell_table(5), Some(vec![1, 1, 2, 5, 15, 52]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn bell_table(n: usize) -> Option<Vec<usize>> {
+    let mut bells = Vec::with_capacity(n + 1);
+    bells.push(1);
+    // row of the Bell triangle starting with B(i)
+    let mut row = vec![1usize];
+    for _ in 0..n {
+        let mut next = Vec::with_capacity(row.len() + 1);
+        next.push(*row.last()?);
+        bells.push(next[0]);
+        for &x in &row {
+            let v = next.last()?.checked_add(x);
+            // The last entry of the row is B(i + 2), which may overflow before B(n) does.
+            match v {
+                Some(v) => next.push(v),
+                None if bells.len() == n + 1 => return Some(bells),
+                None => return None,
+            }
+        }
+        row = next;
+    }
+    Some(bells)
+}
+
+/// The `catalan_table` function returns the Catalan numbers C_0, ..., C_n.
+///
+/// Arguments:
+///
+/// * `n`: The largest index.
+///
+/// Returns:
+///
+/// `n + 1` Catalan numbers, or `None` if any of them overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::catalan_table;
+///
+/// assert_eq!(catalan_table(5), Some(vec![1, 1, 2, 5, 14, 42]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn catalan_table(n: usize) -> Option<Vec<usize>> {
+    (0..=n).map(checked_catalan).collect()
+}
+
+/// The `derangement_table` function returns the derangement numbers D(0), ..., D(n).
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// `n + 1` derangement numbers, or `None` if any of them overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::derangement_table;
+///
+/// assert_eq!(derangement_table(5), Some(vec![1, 0, 1, 2, 9, 44]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn derangement_table(n: usize) -> Option<Vec<usize>> {
+    let mut table: Vec<usize> = vec![1, 0];
+    for i in 2..=n {
+        let next = (i - 1).checked_mul(table[i - 1].checked_add(table[i - 2])?)?;
+        table.push(next);
+    }
+    table.truncate(n + 1);
+    Some(table)
+}
+
+/// The `involution_table` function returns the involution numbers I(0), ..., I(n).
+///
+/// Arguments:
+///
+/// * `n`: The largest number of elements.
+///
+/// Returns:
+///
+/// `n + 1` involution numbers, or `None` if any of them overflows `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::counting::involution_table;
+///
+/// assert_eq!(involution_table(5), Some(vec![1, 1, 2, 4, 10, 26]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn involution_table(n: usize) -> Option<Vec<usize>> {
+    let mut table: Vec<usize> = vec![1, 1];
+    for i in 2..=n {
+        let next = (i - 1)
+            .checked_mul(table[i - 2])?
+            .checked_add(table[i - 1])?;
+        table.push(next);
+    }
+    table.truncate(n + 1);
+    Some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stirling1st() {
+        assert_eq!(stirling1st(0, 0), 1);
+        assert_eq!(stirling1st(5, 0), 0);
+        assert_eq!(stirling1st(6, 3), 225);
+        assert_eq!(stirling1st(10, 1), 362_880);
+        assert_eq!(stirling1st_signed(6, 3), -225);
+        assert_eq!(stirling1st_signed(6, 4), 85);
+        assert_eq!(
+            checked_stirling1st(1 << 20, (1 << 20) - 1),
+            Some((1 << 19) * ((1 << 20) - 1))
+        );
+        // c(n, n - 2) = C(n, 4) + 2 C(n + 1, 4), without a loop over k
+        let n: u128 = 1 << 20;
+        let expected = checked_comb_u128(n, 4).unwrap() + 2 * checked_comb_u128(n + 1, 4).unwrap();
+        assert_eq!(checked_stirling1st_u128(n, n - 2), Some(expected));
+        assert_eq!(checked_stirling1st(1 << 30, (1 << 30) - 2), None);
+        assert_eq!(checked_stirling1st_u128(u128::MAX, u128::MAX - 3), None);
+        // Triangle c(n, k) in u128, saturating where it overflows
+        let mut row = [Some(0u128); 61];
+        row[0] = Some(1);
+        for n in 0..60u128 {
+            for k in 0..=n + 1 {
+                assert_eq!(
+                    checked_stirling1st_u128(n, k),
+                    row[k as usize],
+                    "c({n}, {k})"
+                );
+            }
+            for k in (1..=n as usize + 1).rev() {
+                row[k] = match (row[k], row[k - 1]) {
+                    (Some(a), Some(b)) => a.checked_mul(n).and_then(|a| a.checked_add(b)),
+                    _ => None,
+                };
+            }
+            row[0] = Some(0);
+        }
+        // sum over k of c(n, k) is n!
+        for n in 0..=20 {
+            let total: usize = (0..=n).map(|k| stirling1st(n, k)).sum();
+            assert_eq!(Some(total), crate::checked_factorial(n));
+        }
+        // sum over k of s(n, k) is 0 for n >= 2
+        for n in 2..=20 {
+            let total: isize = (0..=n).map(|k| stirling1st_signed(n, k)).sum();
+            assert_eq!(total, 0);
+        }
+    }
+
+    #[test]
+    fn test_bell_lah_catalan_multinomial() {
+        let bells = [1, 1, 2, 5, 15, 52, 203, 877, 4140, 21147, 115_975];
+        for (n, &b) in bells.iter().enumerate() {
+            assert_eq!(bell(n), b);
+        }
+        assert_eq!(lah(5, 2), 240);
+        assert_eq!(lah(5, 5), 1);
+        assert_eq!(lah(2, 3), 0);
+        let catalans = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862];
+        for (n, &c) in catalans.iter().enumerate() {
+            assert_eq!(catalan(n), c);
+        }
+        assert_eq!(multinomial(&[2, 2, 2]), 90);
+        assert_eq!(multinomial(&[0, 5]), 1);
+        let derangements = [1, 0, 1, 2, 9, 44, 265, 1854, 14833, 133_496];
+        let involutions = [1, 1, 2, 4, 10, 26, 76, 232, 764, 2620];
+        for n in 0..10 {
+            assert_eq!(derangement(n), derangements[n]);
+            assert_eq!(involution(n), involutions[n]);
+        }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_overflow_boundaries() {
+        assert_eq!(checked_bell(25), Some(4_638_590_332_229_999_353));
+        assert_eq!(checked_bell(26), None);
+        assert_eq!(checked_catalan(36), Some(11_959_798_385_860_453_492));
+        assert_eq!(checked_catalan(37), None);
+        assert_eq!(checked_stirling1st(21, 1), Some(2_432_902_008_176_640_000));
+        assert_eq!(checked_stirling1st(22, 1), None);
+        assert_eq!(checked_lah(21, 1), None);
+        assert_eq!(checked_multinomial(&[1; 20]), crate::checked_factorial(20));
+        assert_eq!(checked_multinomial(&[1; 21]), None);
+        assert_eq!(checked_derangement(20), Some(895_014_631_192_902_121));
+        assert_eq!(checked_derangement(21), None);
+        assert_eq!(checked_involution(31), Some(3_666_624_057_550_245_376));
+        assert_eq!(checked_involution(32), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_tables_match_scalars() {
+        let n = 20;
+        let c = stirling1st_table(n).unwrap();
+        let s = stirling1st_signed_table(n).unwrap();
+        let t = stirling2nd_table(n).unwrap();
+        let l = lah_table(19).unwrap();
+        for i in 0..=n {
+            for j in 0..=i {
+                assert_eq!(c[i][j], stirling1st(i, j));
+                assert_eq!(s[i][j], stirling1st_signed(i, j));
+                assert_eq!(Some(t[i][j]), crate::checked_stirling2nd(i, j));
+                if i <= 19 {
+                    assert_eq!(l[i][j], lah(i, j));
+                }
+            }
+        }
+        let b = bell_table(n).unwrap();
+        let cat = catalan_table(n).unwrap();
+        for i in 0..=n {
+            assert_eq!(b[i], bell(i));
+            assert_eq!(cat[i], catalan(i));
+        }
+        assert_eq!(
+            derangement_table(n),
+            (0..=n).map(checked_derangement).collect()
+        );
+        assert_eq!(
+            involution_table(n),
+            (0..=n).map(checked_involution).collect()
+        );
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(bell_table(25).map(|b| b[25]), Some(bell(25)));
+            assert_eq!(bell_table(26), None);
+            assert_eq!(
+                stirling1st_table(21).map(|t| t[21][1]),
+                Some(stirling1st(21, 1))
+            );
+            assert_eq!(stirling1st_table(22), None);
+            assert_eq!(lah_table(20), None);
+            assert_eq!(derangement_table(21), None);
+            assert_eq!(involution_table(32), None);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_counts_match_iterators() {
+        use crate::{EmkCombIter, SetPartitionIter, SjtIter};
+
+        for n in 3..=9 {
+            // Every partition has between 1 and n blocks; the iterators cover 1 < k < n.
+            let partitions: usize = (2..n)
+                .map(|k| SetPartitionIter::new(n, k).count() + 1)
+                .sum::<usize>()
+                + 2;
+            assert_eq!(partitions, bell(n));
+            let perms = SjtIter::new(n).count();
+            assert_eq!(perms, (0..=n).map(|k| stirling1st(n, k)).sum::<usize>());
+            for k in 1..n {
+                assert_eq!(EmkCombIter::new(n, k).count() + 1, multinomial(&[k, n - k]));
+            }
+        }
+    }
+}
@@ -16,14 +16,405 @@
 //! Difference set generation
 //!
 //! This module provides functionality for generating difference sets,
-//! which are combinatorial structures with specific properties.
+//! which are combinatorial structures with specific properties, along with
+//! the related Golomb rulers and modular Sidon sets, in which every
+//! difference occurs at most once.
+//!
+//! The searches become infeasible quickly as the modulus grows; for the
+//! classical families, [`singer_diffset`] and [`paley_diffset`] construct a
+//! difference set directly.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::FusedIterator;
 
 use genawaiter::sync::{Gen, GenBoxed};
 
-/// Generate difference sets
+/// Error returned when no difference set with the requested parameters exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffsetError {
+    /// The set size `d` is less than 2 or not less than the modulus `n`.
+    InvalidSize {
+        /// The modulus
+        n: usize,
+        /// The set size
+        d: usize,
+    },
+    /// The `d * (d - 1)` differences of a set of size `d` cannot cover the
+    /// `n - 1` non-zero residues modulo `n` exactly `lambda` times each.
+    Infeasible {
+        /// The modulus
+        n: usize,
+        /// The set size
+        d: usize,
+        /// The number of times each non-zero difference should occur
+        lambda: usize,
+    },
+    /// A Singer difference set was requested for an order `q` that is not a
+    /// prime power.
+    NotPrimePower {
+        /// The order
+        q: usize,
+    },
+    /// A Paley difference set was requested for a modulus `p` that is not a
+    /// prime congruent to 3 modulo 4.
+    NotPaleyPrime {
+        /// The modulus
+        p: usize,
+    },
+}
+
+impl fmt::Display for DiffsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DiffsetError::InvalidSize { n, d } => {
+                write!(
+                    f,
+                    "set size {d} must be at least 2 and less than the modulus {n}"
+                )
+            }
+            DiffsetError::Infeasible { n, d, lambda } => write!(
+                f,
+                "no ({n}, {d}, {lambda}) difference set: d * (d - 1) must equal lambda * (n - 1)"
+            ),
+            DiffsetError::NotPrimePower { q } => write!(f, "order {q} is not a prime power"),
+            DiffsetError::NotPaleyPrime { p } => {
+                write!(f, "{p} is not a prime congruent to 3 modulo 4")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DiffsetError {}
+
+/// Checks the necessary conditions for a (n, d, λ) difference set.
+fn check_params(n: usize, d: usize, lambda: usize) -> Result<(), DiffsetError> {
+    if d < 2 || d >= n {
+        return Err(DiffsetError::InvalidSize { n, d });
+    }
+    let pairs = d.checked_mul(d - 1);
+    if pairs.is_none() || pairs != lambda.checked_mul(n - 1) {
+        return Err(DiffsetError::Infeasible { n, d, lambda });
+    }
+    Ok(())
+}
+
+/// Set of differences `1..=n/2` seen so far, kept as a bitset
 ///
-/// This generator produces all possible difference sets of size `d` for a set of size `n`,
-/// using a threshold for pruning the search space.
+/// A difference `x` modulo `n` is identified with `n - x`, so only the
+/// smaller of the two is stored.
+#[derive(Debug, Clone)]
+struct DiffTable {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl DiffTable {
+    fn new(max: usize) -> Self {
+        Self {
+            words: vec![0; max / 64 + 1],
+            len: 0,
+        }
+    }
+
+    /// Adds `x`, returning `false` if it was already present.
+    fn insert(&mut self, x: usize) -> bool {
+        let (word, bit) = (x / 64, 1u64 << (x % 64));
+        let fresh = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        self.len += usize::from(fresh);
+        fresh
+    }
+
+    /// Removes `x`, which must be present.
+    fn remove(&mut self, x: usize) {
+        self.words[x / 64] &= !(1u64 << (x % 64));
+        self.len -= 1;
+    }
+
+    /// The number of distinct differences seen.
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The sum of the `count` smallest positive differences not seen yet.
+    fn sum_absent(&self, count: usize) -> usize {
+        let (mut sum, mut left) = (0, count);
+        let mut x = 1;
+        while left > 0 {
+            if self
+                .words
+                .get(x / 64)
+                .map_or(true, |w| w & 1 << (x % 64) == 0)
+            {
+                sum += x;
+                left -= 1;
+            }
+            x += 1;
+        }
+        sum
+    }
+}
+
+/// Sorted elements, starting with 0, whose pairwise differences are distinct
+///
+/// This is the search core shared by [`GolombIter`] and [`SidonIter`]. A
+/// difference is a plain distance when `modulus` is 0, and is otherwise taken
+/// modulo `modulus` like in [`DiffTable`]. Elements are placed one at a time
+/// and removed again in place when the search backtracks.
+#[derive(Debug, Clone)]
+struct DistinctDiffs {
+    modulus: usize,
+    set: Vec<usize>,
+    table: DiffTable,
+    /// Smallest candidate for the next element not tried yet.
+    next: usize,
+}
+
+impl DistinctDiffs {
+    fn new(max: usize, modulus: usize) -> Self {
+        Self {
+            modulus,
+            set: vec![0],
+            table: DiffTable::new(max),
+            next: 0,
+        }
+    }
+
+    /// The table entry of `diff`, or `None` if `diff` equals its own negative.
+    fn key(&self, diff: usize) -> Option<usize> {
+        match self.modulus {
+            0 => Some(diff),
+            n if 2 * diff == n => None,
+            n => Some(diff.min(n - diff)),
+        }
+    }
+
+    /// Appends `x`, returning `false` and leaving everything unchanged if a
+    /// difference would repeat.
+    fn push(&mut self, x: usize) -> bool {
+        for i in 0..self.set.len() {
+            let fresh = match self.key(x - self.set[i]) {
+                Some(key) => self.table.insert(key),
+                None => false,
+            };
+            if !fresh {
+                for j in 0..i {
+                    let key = self.key(x - self.set[j]).unwrap();
+                    self.table.remove(key);
+                }
+                return false;
+            }
+        }
+        self.set.push(x);
+        true
+    }
+
+    /// Removes the last element and its differences.
+    fn pop(&mut self) -> usize {
+        let x = self.set.pop().unwrap();
+        for i in 0..self.set.len() {
+            let key = self.key(x - self.set[i]).unwrap();
+            self.table.remove(key);
+        }
+        x
+    }
+
+    /// Finds the next set of `size` elements. `bounds(set, table)` gives the
+    /// inclusive range of candidates for the element after `set`.
+    fn search<B>(&mut self, size: usize, bounds: B) -> Option<Vec<usize>>
+    where
+        B: Fn(&[usize], &DiffTable) -> (usize, usize),
+    {
+        loop {
+            let last = *self.set.last()?;
+            let found = (self.set.len() >= size).then(|| self.set.clone());
+            if found.is_none() {
+                let (lo, hi) = bounds(&self.set, &self.table);
+                let x = self.next.max(lo).max(last + 1);
+                if x <= hi {
+                    self.next = x + 1;
+                    self.push(x);
+                    continue;
+                }
+            }
+            if self.set.len() == 1 {
+                self.set.clear();
+            } else {
+                self.next = self.pop() + 1;
+            }
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+}
+
+/// A node of the search: the element `a[t]` has just been placed.
+#[derive(Debug, Clone)]
+struct DiffsetFrame {
+    t: usize,
+    p: usize,
+    pc: u8,
+    /// Next candidate for `a[t + 1]`, counting down.
+    next: usize,
+    /// Length of the trail when the node was entered.
+    mark: usize,
+}
+
+/// Iterator over planar difference sets
+///
+/// `DiffsetIter` yields exactly the same sets as [`diffset_gen`], in the same
+/// order. The search runs on an explicit stack and updates a single table of
+/// differences in place, undoing each node's changes when it is left, so each
+/// set is yielded as soon as it is found and memory stays at O(n) throughout.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{is_diffset, DiffsetIter};
+///
+/// // the first planar difference set modulo 73 = 9 * 8 + 1
+/// let set = DiffsetIter::new(73, 9, 2).unwrap().next().unwrap();
+/// assert!(is_diffset(&set, 73));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiffsetIter {
+    n: usize,
+    d: usize,
+    threshold: usize,
+    n1: usize,
+    a: Vec<usize>,
+    b: Vec<usize>,
+    table: DiffTable,
+    /// Differences added to `table`, in order, so that they can be undone.
+    trail: Vec<usize>,
+    stack: Vec<DiffsetFrame>,
+    /// Candidates for `a[1]` that have not been tried yet.
+    firsts: core::iter::Rev<core::ops::RangeInclusive<usize>>,
+}
+
+impl DiffsetIter {
+    /// Creates the search for planar difference sets of size `d` modulo `n`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiffsetError`] under the same conditions as [`diffset_gen`].
+    pub fn new(n: usize, d: usize, threshold: usize) -> Result<Self, DiffsetError> {
+        check_params(n, d, 1)?;
+        let n2 = n / 2;
+        let mut a = vec![0; d + 1];
+        a[d] = n;
+        Ok(Self {
+            n,
+            d,
+            threshold,
+            n1: n2 - d * (d - 1) / 2,
+            a,
+            b: vec![0; d + 1],
+            table: DiffTable::new(n2),
+            trail: Vec::new(),
+            stack: Vec::new(),
+            firsts: ((n - 1) / d + 1..=n - d + 1).rev(),
+        })
+    }
+
+    fn push(&mut self, t: usize, p: usize) {
+        self.stack.push(DiffsetFrame {
+            t,
+            p,
+            pc: 0,
+            next: 0,
+            mark: self.trail.len(),
+        });
+    }
+
+    fn pop(&mut self) {
+        if let Some(frame) = self.stack.pop() {
+            for x in self.trail.drain(frame.mark..) {
+                self.table.remove(x);
+            }
+        }
+    }
+}
+
+impl Iterator for DiffsetIter {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        loop {
+            let Some(top) = self.stack.len().checked_sub(1) else {
+                self.a[1] = self.firsts.next()?;
+                self.b[1] = 1;
+                self.push(1, 1);
+                continue;
+            };
+            let DiffsetFrame { t, p, pc, .. } = self.stack[top];
+            let t1 = t + 1;
+            match pc {
+                0 => {
+                    for i in 0..t {
+                        let diff = self.a[t] - self.a[i];
+                        let x = diff.min(self.n - diff);
+                        if self.table.insert(x) {
+                            self.trail.push(x);
+                        }
+                    }
+                    if t >= self.threshold && self.table.len() < self.n1 + t * t1 / 2 {
+                        self.pop();
+                        continue;
+                    }
+                    if t1 >= self.d {
+                        let set = self.a[..self.d].to_vec();
+                        self.pop();
+                        return Some(set);
+                    }
+                    let tail = self.n - self.d + t1;
+                    let max = self.a[t1 - p] + self.a[p];
+                    let frame = &mut self.stack[top];
+                    if max <= tail {
+                        frame.pc = 1;
+                        frame.next = max - 1;
+                        self.a[t1] = max;
+                        self.b[t1] = self.b[t1 - p];
+                        self.push(t1, p);
+                    } else {
+                        frame.pc = 2;
+                        frame.next = tail;
+                    }
+                }
+                1 => {
+                    self.stack[top].pc = 2;
+                    if self.b[t1] == 0 {
+                        self.b[t1] = 1;
+                        self.push(t1, t1);
+                    }
+                }
+                _ => {
+                    let frame = &mut self.stack[top];
+                    let j = frame.next;
+                    if j > self.a[t] {
+                        frame.next = j - 1;
+                        self.a[t1] = j;
+                        self.b[t1] = 1;
+                        self.push(t1, t1);
+                    } else {
+                        self.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for DiffsetIter {}
+
+/// Generate planar difference sets
+///
+/// This generator produces all planar difference sets of size `d` modulo `n`,
+/// i.e. sets in which every non-zero residue occurs exactly once as a
+/// difference, using a threshold for pruning the search space. Such sets exist
+/// only for `n = d * (d - 1) + 1`.
 ///
 /// # Arguments
 ///
@@ -35,130 +426,219 @@ use genawaiter::sync::{Gen, GenBoxed};
 ///
 /// A generator that yields vectors representing difference sets
 ///
+/// # Errors
+///
+/// Returns [`DiffsetError`] if `d < 2`, `d >= n` or `n != d * (d - 1) + 1`
+///
 /// # Examples
 ///
 /// ```
-/// use ecgen::diffset::diffset_gen;
+/// use ecgen::diffset::{diffset_gen, is_diffset, DiffsetError};
 ///
 /// // Generate difference sets for n=13, d=4
-/// let gen = diffset_gen(13, 4, 2);
+/// let gen = diffset_gen(13, 4, 2).unwrap();
 /// let mut sets: Vec<Vec<usize>> = Vec::new();
 /// for set in gen {
 ///     sets.push(set);
 /// }
-/// // Should find the difference set {0,1,3,9} modulo 13
+/// // Each is equivalent to the difference set {0,1,3,9} modulo 13
+/// assert!(!sets.is_empty());
+/// assert!(sets.iter().all(|set| is_diffset(set, 13)));
+///
+/// assert_eq!(
+///     diffset_gen(14, 4, 2).err(),
+///     Some(DiffsetError::Infeasible { n: 14, d: 4, lambda: 1 })
+/// );
 /// ```
-pub fn diffset_gen(n: usize, d: usize, threshold: usize) -> GenBoxed<Vec<usize>> {
-    Gen::new_boxed(|co| async move {
-        if n > d * (d - 1) + 1 {
-            return;
+pub fn diffset_gen(
+    n: usize,
+    d: usize,
+    threshold: usize,
+) -> Result<GenBoxed<Vec<usize>>, DiffsetError> {
+    check_params(n, d, 1)?;
+    let iter = DiffsetIter::new(n, d, threshold)?;
+    Ok(Gen::new_boxed(|co| async move {
+        for set in iter {
+            co.yield_(set).await;
         }
+    }))
+}
 
-        let max_n = 70;
-        if n > max_n {
-            return;
+/// Generate (v, k, λ) difference sets
+///
+/// A (v, k, λ) difference set is a set of `k` residues modulo `v` such that every
+/// non-zero residue occurs exactly `λ` times as a difference of two of its
+/// elements. Planar difference sets are the case `λ = 1`, for which
+/// [`diffset_gen`] is considerably faster. Other families include the Singer
+/// parameters `((q^(m+1) - 1)/(q - 1), (q^m - 1)/(q - 1), (q^(m-1) - 1)/(q - 1))`
+/// and the Hadamard parameters `(4t - 1, 2t - 1, t - 1)`.
+///
+/// Every translate of a difference set is again a difference set. The search
+/// yields each set at most once per translation class, normalized so that it
+/// starts with 0 and its first gap `a[1] - a[0]` is a largest gap between
+/// cyclically consecutive elements; sets whose largest gap occurs more than
+/// once are yielded once for each such gap.
+///
+/// # Arguments
+///
+/// * `v` - The modulus (elements are 0, 1, ..., v-1)
+/// * `k` - The size of each difference set
+/// * `lambda` - The number of times each non-zero difference occurs
+///
+/// # Returns
+///
+/// A generator that yields the difference sets as sorted vectors
+///
+/// # Errors
+///
+/// Returns [`DiffsetError`] unless `2 <= k < v` and `k * (k - 1) == lambda * (v - 1)`
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{diffset_lambda_gen, is_diffset_lambda};
+///
+/// // the quadratic residues modulo 11 form an (11, 5, 2) difference set
+/// let sets: Vec<Vec<usize>> = diffset_lambda_gen(11, 5, 2).unwrap().into_iter().collect();
+/// assert!(!sets.is_empty());
+/// assert!(sets.iter().all(|set| is_diffset_lambda(set, 11, 2)));
+/// ```
+pub fn diffset_lambda_gen(
+    v: usize,
+    k: usize,
+    lambda: usize,
+) -> Result<GenBoxed<Vec<usize>>, DiffsetError> {
+    let iter = DiffsetLambdaIter::new(v, k, lambda)?;
+    Ok(Gen::new_boxed(|co| async move {
+        for set in iter {
+            co.yield_(set).await;
         }
+    }))
+}
 
-        let d_times_d_minus_1 = d * (d - 1);
-        let n_minus_d = n - d;
-        let n2 = n / 2;
-        let n1 = n2 - d_times_d_minus_1 / 2;
-        let size_n = n2 + 1;
+/// Iterator over (v, k, λ) difference sets
+///
+/// `DiffsetLambdaIter` yields exactly the same sets as [`diffset_lambda_gen`],
+/// in the same order. Like [`DiffsetIter`], it searches on an explicit stack,
+/// updates a single table of difference counts in place and undoes each
+/// element's differences when the element is removed, so each set is yielded
+/// as soon as it is found.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{is_diffset_lambda, DiffsetLambdaIter};
+///
+/// let set = DiffsetLambdaIter::new(15, 7, 3).unwrap().next().unwrap();
+/// assert!(is_diffset_lambda(&set, 15, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiffsetLambdaIter {
+    v: usize,
+    k: usize,
+    lambda: usize,
+    /// The elements placed so far, starting with 0.
+    set: Vec<usize>,
+    /// `count[d]` is the number of times `d` occurs as a difference in `set`.
+    count: Vec<usize>,
+    /// Smallest candidate for the next element not tried yet.
+    next: usize,
+    /// Candidates for the first gap `set[1]` that have not been tried yet.
+    gaps: core::ops::RangeInclusive<usize>,
+}
 
-        let mut a = vec![0usize; d + 1];
-        let mut b = vec![0usize; d + 1];
-        a[d] = n;
-        a[0] = 0;
+impl DiffsetLambdaIter {
+    /// Creates the search for (v, k, λ) difference sets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DiffsetError`] under the same conditions as [`diffset_lambda_gen`].
+    pub fn new(v: usize, k: usize, lambda: usize) -> Result<Self, DiffsetError> {
+        check_params(v, k, lambda)?;
+        Ok(Self {
+            v,
+            k,
+            lambda,
+            set: vec![0],
+            count: vec![0; v],
+            next: 0,
+            // the largest of the k gaps is at least v / k
+            gaps: (v - 1) / k + 1..=v - k + 1,
+        })
+    }
 
-        let mut differences = vec![0i8; size_n];
-        differences[0] = 1;
+    /// Appends `x`, returning `false` and leaving everything unchanged if a
+    /// difference would occur more than `lambda` times.
+    fn push(&mut self, x: usize) -> bool {
+        let v = self.v;
+        for i in 0..self.set.len() {
+            let d = x - self.set[i];
+            self.count[d] += 1;
+            self.count[v - d] += 1;
+            if self.count[d] > self.lambda || self.count[v - d] > self.lambda {
+                for &s in &self.set[..=i] {
+                    self.count[x - s] -= 1;
+                    self.count[v - (x - s)] -= 1;
+                }
+                return false;
+            }
+        }
+        self.set.push(x);
+        true
+    }
 
-        let ctx = DiffsetContext {
-            n,
-            d,
-            threshold,
-            n1,
-            n2,
-            n_minus_d,
-        };
-
-        for j in ((n - 1) / d + 1..=n - d + 1).rev() {
-            a[1] = j;
-            b[1] = 1;
-            let mut result = Vec::new();
-            diffset_recursive_impl(&mut a, &mut b, 1, 1, 1, &mut differences, &ctx, &mut result);
-            for set in result {
-                co.yield_(set).await;
+    /// Removes the last element and its differences; the next candidate for
+    /// its position is the one after it.
+    fn pop(&mut self) {
+        if let Some(x) = self.set.pop() {
+            for &s in &self.set {
+                self.count[x - s] -= 1;
+                self.count[self.v - (x - s)] -= 1;
             }
+            self.next = x + 1;
         }
-    })
+    }
 }
 
-struct DiffsetContext {
-    n: usize,
-    d: usize,
-    threshold: usize,
-    n1: usize,
-    n2: usize,
-    n_minus_d: usize,
-}
+impl Iterator for DiffsetLambdaIter {
+    type Item = Vec<usize>;
 
-#[allow(clippy::too_many_arguments)]
-fn diffset_recursive_impl(
-    a: &mut [usize],
-    b: &mut [usize],
-    t: usize,
-    p: usize,
-    tt: usize,
-    diffset: &mut [i8],
-    ctx: &DiffsetContext,
-    result: &mut Vec<Vec<usize>>,
-) {
-    let mut differences = diffset.to_owned();
-
-    for i in 0..t {
-        let diff = a[t] - a[i];
-        let n_diff = ctx.n - diff;
-        let min_diff = if diff <= n_diff { diff } else { n_diff };
-        differences[min_diff] = 1;
-    }
-
-    if t >= ctx.threshold {
-        let count = differences[1..=ctx.n2].iter().filter(|&&x| x != 0).count();
-        if count < ctx.n1 + tt {
-            return;
-        }
-    }
-
-    let t1 = t + 1;
-    if t1 >= ctx.d {
-        // Found a valid difference set
-        result.push(a[0..ctx.d].to_vec());
-    } else {
-        let mut tail = ctx.n_minus_d + t1;
-        let max = a[t1 - p] + a[p];
-        let tt1 = t1 * (t1 + 1) / 2;
-
-        if max <= tail {
-            a[t1] = max;
-            b[t1] = b[t1 - p];
-            diffset_recursive_impl(a, b, t1, p, tt1, &mut differences, ctx, result);
-
-            if b[t1] == 0 {
-                b[t1] = 1;
-                diffset_recursive_impl(a, b, t1, t1, tt1, &mut differences, ctx, result);
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let (v, k) = (self.v, self.k);
+        loop {
+            let t = self.set.len();
+            if t == 1 {
+                let gap = self.gaps.next()?;
+                self.push(gap);
+                continue;
             }
-            tail = max - 1;
-        }
-
-        for j in (a[t] + 1..=tail).rev() {
-            a[t1] = j;
-            b[t1] = 1;
-            diffset_recursive_impl(a, b, t1, t1, tt1, &mut differences, ctx, result);
+            let gap = self.set[1];
+            let last = self.set[t - 1];
+            if t == k {
+                // the closing gap may not exceed the first one either
+                let found = (v - last <= gap).then(|| self.set.clone());
+                self.pop();
+                if found.is_some() {
+                    return found;
+                }
+                continue;
+            }
+            // leave room for the remaining elements, keep the gap to them
+            // small, and let them close a gap of at most (k - t) * gap
+            let hi = (last + gap).min(v - (k - t));
+            let x = self.next.max(last + 1).max(v.saturating_sub((k - t) * gap));
+            if x > hi {
+                self.pop();
+                continue;
+            }
+            self.next = x + 1;
+            self.push(x);
         }
     }
 }
 
+impl FusedIterator for DiffsetLambdaIter {}
+
 /// Check if a set is a valid difference set
 ///
 /// This function verifies whether a given set is a difference set modulo n,
@@ -182,28 +662,529 @@ fn diffset_recursive_impl(
 /// assert!(is_diffset(&[0, 1, 3, 9], 13));
 /// ```
 pub fn is_diffset(diffset: &[usize], n: usize) -> bool {
-    if diffset.is_empty() {
+    is_diffset_lambda(diffset, n, 1)
+}
+
+/// Check if a set is a (v, k, λ) difference set
+///
+/// This function verifies whether every non-zero residue modulo `v` occurs
+/// exactly `lambda` times as a difference `a - b` of two distinct elements of
+/// the set. Elements are taken modulo `v`, and a set containing the same
+/// residue twice is never a difference set.
+///
+/// # Arguments
+///
+/// * `set` - The set to check
+/// * `v` - The modulus
+/// * `lambda` - The required number of occurrences of each non-zero difference
+///
+/// # Returns
+///
+/// `true` if the set is a (v, k, λ) difference set with `k = set.len()`,
+/// `false` otherwise
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::is_diffset_lambda;
+///
+/// // the quadratic residues modulo 23 form a (23, 11, 5) difference set
+/// assert!(is_diffset_lambda(&[1, 2, 3, 4, 6, 8, 9, 12, 13, 16, 18], 23, 5));
+/// assert!(is_diffset_lambda(&[0, 1, 2, 4, 5, 8, 10], 15, 3));
+/// assert!(!is_diffset_lambda(&[0, 1, 2, 4, 5, 8, 11], 15, 3));
+/// ```
+pub fn is_diffset_lambda(set: &[usize], v: usize, lambda: usize) -> bool {
+    if set.is_empty() || v == 0 {
         return false;
     }
 
-    let mut differences = vec![0i8; n];
-
-    for i in 0..diffset.len() {
-        for j in (i + 1)..diffset.len() {
-            let diff = (diffset[j] - diffset[i]) % n;
-            let n_diff = (n - diff) % n;
+    let mut differences = vec![0usize; v];
+    for (i, &a) in set.iter().enumerate() {
+        for &b in &set[i + 1..] {
+            let diff = (a % v + v - b % v) % v;
+            if diff == 0 {
+                return false;
+            }
             differences[diff] += 1;
-            differences[n_diff] += 1;
+            differences[v - diff] += 1;
+        }
+    }
+
+    differences[1..].iter().all(|&c| c == lambda)
+}
+
+/// Construct a Singer difference set
+///
+/// The non-zero elements of the finite field GF(q^(m+1)) are the powers of a
+/// primitive element `α`, and those with trace 0 over GF(q) form, together
+/// with 0, a hyperplane. The exponents `i` in `0..v` for which `α^i` lies in
+/// the hyperplane form a (v, k, λ) difference set modulo
+/// `v = (q^(m+1) - 1) / (q - 1)`, with `k = (q^m - 1) / (q - 1)` and
+/// `λ = (q^(m-1) - 1) / (q - 1)`. For `m = 2` this is a planar difference set
+/// of order `q`, found without any search.
+///
+/// The field is built as GF(p)\[x\] modulo the first primitive polynomial
+/// found, and all its `q^(m+1)` elements are listed, so the time and memory
+/// taken are linear in the size of the field.
+///
+/// # Arguments
+///
+/// * `q` - The order of the base field, a prime power
+/// * `m` - The dimension of the hyperplanes, at least 2
+///
+/// # Returns
+///
+/// The difference set as a sorted vector
+///
+/// # Errors
+///
+/// Returns [`DiffsetError::NotPrimePower`] if `q` is not a prime power, and
+/// [`DiffsetError::InvalidSize`] if `m < 2`
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{is_diffset, is_diffset_lambda, singer_diffset};
+///
+/// // the plane of order 9 without searching 91 residues
+/// let set = singer_diffset(9, 2).unwrap();
+/// assert_eq!(set.len(), 10);
+/// assert!(is_diffset(&set, 91));
+///
+/// // the points of a hyperplane of PG(3, 3): a (40, 13, 4) difference set
+/// assert!(is_diffset_lambda(&singer_diffset(3, 3).unwrap(), 40, 4));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `q^(m+1)` overflows `usize`.
+pub fn singer_diffset(q: usize, m: usize) -> Result<Vec<usize>, DiffsetError> {
+    let (p, e) = prime_power(q).ok_or(DiffsetError::NotPrimePower { q })?;
+    let pow = |j: usize| {
+        q.checked_pow(j as u32)
+            .expect("q^(m+1) must not overflow usize")
+    };
+    let field = pow(m + 1);
+    let v = (field - 1) / (q - 1);
+    let k = (pow(m) - 1) / (q - 1);
+    let lambda = (pow(m.saturating_sub(1)) - 1) / (q - 1);
+    check_params(v, k, lambda)?;
+
+    let degree = e * (m + 1);
+    let powers = primitive_powers(p, degree);
+    let power = |i: usize| &powers[i * degree..(i + 1) * degree];
+    let units = field - 1;
+    let mut trace = vec![0; degree];
+    Ok((0..v)
+        .filter(|&i| {
+            // Tr(α^i) = α^i + α^(i q) + ... + α^(i q^m)
+            trace.iter_mut().for_each(|c| *c = 0);
+            let mut j = i;
+            for _ in 0..=m {
+                for (c, &x) in trace.iter_mut().zip(power(j)) {
+                    *c = (*c + x) % p;
+                }
+                j = j * q % units;
+            }
+            trace.iter().all(|&c| c == 0)
+        })
+        .collect())
+}
+
+/// Splits `q` into `(p, e)` with `q = p^e` and `p` prime.
+fn prime_power(q: usize) -> Option<(usize, usize)> {
+    let p = (2..q).take_while(|d| d * d <= q).find(|d| q % d == 0);
+    let p = match p {
+        Some(p) => p,
+        None if q >= 2 => return Some((q, 1)),
+        None => return None,
+    };
+    let (mut rest, mut e) = (q, 0);
+    while rest % p == 0 {
+        rest /= p;
+        e += 1;
+    }
+    (rest == 1).then_some((p, e))
+}
+
+/// The powers `x^0, x^1, ..., x^(p^n - 2)` in GF(p)\[x\] modulo the first
+/// primitive polynomial of degree `n`, as consecutive rows of `n`
+/// coefficients.
+///
+/// A monic polynomial `f` of degree `n` is primitive exactly when `x` has
+/// order `p^n - 1` modulo `f`, so each candidate is tested by walking through
+/// the powers of `x` until they return to 1.
+fn primitive_powers(p: usize, n: usize) -> Vec<usize> {
+    let units = p.pow(n as u32) - 1;
+    let mut powers = Vec::with_capacity(units * n);
+    // x^n is reduced to the polynomial with coefficients `low`
+    let mut low = vec![0; n];
+    loop {
+        // next candidate, skipping the ones divisible by x
+        loop {
+            let carry = low.iter_mut().position(|c| {
+                *c = (*c + 1) % p;
+                *c != 0
+            });
+            if carry.is_none() || low[0] != 0 {
+                break;
+            }
+        }
+        powers.clear();
+        let mut cur = vec![0; n];
+        cur[0] = 1;
+        for _ in 0..units {
+            powers.extend_from_slice(&cur);
+            let top = cur[n - 1];
+            for j in (0..n).rev() {
+                let shifted = if j > 0 { cur[j - 1] } else { 0 };
+                cur[j] = (shifted + top * low[j]) % p;
+            }
+            if cur[0] == 1 && cur[1..].iter().all(|&c| c == 0) {
+                break;
+            }
+        }
+        if powers.len() == units * n {
+            return powers;
+        }
+    }
+}
+
+/// Construct a Paley difference set
+///
+/// For a prime `p` congruent to 3 modulo 4, the non-zero quadratic residues
+/// modulo `p` form a (p, (p - 1) / 2, (p - 3) / 4) difference set.
+///
+/// # Arguments
+///
+/// * `p` - The modulus, a prime congruent to 3 modulo 4
+///
+/// # Returns
+///
+/// The quadratic residues as a sorted vector
+///
+/// # Errors
+///
+/// Returns [`DiffsetError::NotPaleyPrime`] if `p` is not a prime congruent to
+/// 3 modulo 4, and [`DiffsetError::InvalidSize`] for `p = 3`, whose single
+/// residue has no differences
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{is_diffset_lambda, paley_diffset, DiffsetError};
+///
+/// let set = paley_diffset(11).unwrap();
+/// assert_eq!(set, [1, 3, 4, 5, 9]);
+/// assert!(is_diffset_lambda(&set, 11, 2));
+/// assert_eq!(paley_diffset(13), Err(DiffsetError::NotPaleyPrime { p: 13 }));
+/// ```
+pub fn paley_diffset(p: usize) -> Result<Vec<usize>, DiffsetError> {
+    if p % 4 != 3 || prime_power(p) != Some((p, 1)) {
+        return Err(DiffsetError::NotPaleyPrime { p });
+    }
+    check_params(p, (p - 1) / 2, (p - 3) / 4)?;
+    let residues: BTreeSet<usize> = (1..p).map(|x| x * x % p).collect();
+    Ok(residues.into_iter().collect())
+}
+
+/// Iterator over Golomb rulers of a given length
+///
+/// A Golomb ruler with `marks` marks is a set of integers `0 = a[0] < a[1] <
+/// ... < a[marks - 1]` whose pairwise distances are all distinct; its length
+/// is the last mark. `GolombIter` yields every ruler of exactly the given
+/// length in lexicographic order, each once up to reflection: a ruler is
+/// listed only if its first gap is smaller than its last gap.
+///
+/// Asking for the shortest length for which any ruler exists gives the
+/// optimal rulers (see [`optimal_golomb`]); longer lengths give the
+/// near-optimal ones.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::GolombIter;
+///
+/// let rulers: Vec<Vec<usize>> = GolombIter::new(5, 11).collect();
+/// assert_eq!(rulers, [[0, 1, 4, 9, 11], [0, 2, 7, 8, 11]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GolombIter {
+    marks: usize,
+    length: usize,
+    core: DistinctDiffs,
+}
+
+impl GolombIter {
+    /// Creates the search for rulers with `marks` marks and length `length`.
+    pub fn new(marks: usize, length: usize) -> Self {
+        let mut core = DistinctDiffs::new(length, 0);
+        if marks == 0 || (marks == 1) != (length == 0) {
+            core.set.clear();
+        }
+        Self {
+            marks,
+            length,
+            core,
         }
     }
+}
+
+impl Iterator for GolombIter {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let (marks, length) = (self.marks, self.length);
+        self.core.search(marks, |set, table| {
+            let t = set.len();
+            // marks still to be placed after the next one
+            let rest = marks - 1 - t;
+            if rest == 0 {
+                // the last mark, unless its gap would not exceed the first
+                return if t < 2 || set[1] < length - set[t - 1] {
+                    (length, length)
+                } else {
+                    (1, 0)
+                };
+            }
+            // the gaps between the remaining marks are distinct differences
+            // that have not been used yet
+            let mut hi = length.saturating_sub(table.sum_absent(rest));
+            if t == 1 {
+                // the first gap is smaller than the last, so at most half
+                hi = hi.min((length - 1) / 2);
+            }
+            (0, hi)
+        })
+    }
+}
+
+impl FusedIterator for GolombIter {}
+
+/// Generate Golomb rulers
+///
+/// This generator produces the same rulers as [`GolombIter`].
+///
+/// # Arguments
+///
+/// * `marks` - The number of marks
+/// * `length` - The length of each ruler, i.e. its last mark
+///
+/// # Returns
+///
+/// A generator that yields the rulers as sorted vectors starting with 0
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{golomb_gen, is_golomb_ruler};
+///
+/// // the near-optimal rulers with 6 marks, one unit longer than optimal
+/// let rulers: Vec<Vec<usize>> = golomb_gen(6, 18).into_iter().collect();
+/// assert!(!rulers.is_empty());
+/// assert!(rulers.iter().all(|r| is_golomb_ruler(r) && r[5] == 18));
+/// ```
+pub fn golomb_gen(marks: usize, length: usize) -> GenBoxed<Vec<usize>> {
+    Gen::new_boxed(|co| async move {
+        for ruler in GolombIter::new(marks, length) {
+            co.yield_(ruler).await;
+        }
+    })
+}
+
+/// Find an optimal Golomb ruler
+///
+/// This function tries the lengths `marks * (marks - 1) / 2`, which every
+/// ruler needs, and upwards until a ruler exists, and returns the first one
+/// in the order of [`GolombIter`]. The search takes exponential time: in a
+/// release build, 10 marks take well under a second and 11 marks a few
+/// seconds.
+///
+/// # Arguments
+///
+/// * `marks` - The number of marks
+///
+/// # Returns
+///
+/// A shortest Golomb ruler with `marks` marks, or an empty vector if `marks`
+/// is 0
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::optimal_golomb;
+///
+/// assert_eq!(optimal_golomb(4), [0, 1, 4, 6]);
+/// assert_eq!(optimal_golomb(8), [0, 1, 4, 9, 15, 22, 32, 34]);
+/// ```
+pub fn optimal_golomb(marks: usize) -> Vec<usize> {
+    if marks == 0 {
+        return Vec::new();
+    }
+    (marks * (marks - 1) / 2..)
+        .find_map(|length| GolombIter::new(marks, length).next())
+        .unwrap()
+}
+
+/// Check if a set of marks is a Golomb ruler
+///
+/// # Arguments
+///
+/// * `marks` - The marks, in any order
+///
+/// # Returns
+///
+/// `true` if all distances between two distinct marks are distinct and
+/// non-zero, `false` otherwise
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::is_golomb_ruler;
+///
+/// assert!(is_golomb_ruler(&[0, 1, 4, 6]));
+/// assert!(!is_golomb_ruler(&[0, 1, 2, 4]));
+/// ```
+pub fn is_golomb_ruler(marks: &[usize]) -> bool {
+    let mut seen = BTreeSet::new();
+    marks.iter().enumerate().all(|(i, &a)| {
+        marks[i + 1..]
+            .iter()
+            .all(|&b| a != b && seen.insert(a.abs_diff(b)))
+    })
+}
+
+/// Iterator over modular Sidon sets
+///
+/// A Sidon set modulo `n` is a set of residues whose differences `a - b`,
+/// `a != b`, are all distinct modulo `n`; a planar difference set is a Sidon
+/// set with the largest possible size. `SidonIter` yields one representative
+/// of each class under translations: the one that contains 0 and in which the
+/// gap from 0 to the next element is the largest gap between cyclically
+/// consecutive elements. The representatives are listed in lexicographic
+/// order.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{is_diffset, SidonIter};
+///
+/// // the Sidon sets of size 4 modulo 13 are the planar difference sets
+/// let sets: Vec<Vec<usize>> = SidonIter::new(13, 4).collect();
+/// assert_eq!(sets, [[0, 6, 8, 9], [0, 6, 10, 11], [0, 7, 8, 11], [0, 7, 9, 12]]);
+/// assert!(sets.iter().all(|set| is_diffset(set, 13)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SidonIter {
+    n: usize,
+    k: usize,
+    core: DistinctDiffs,
+}
 
-    // Check that all non-zero differences appear exactly once
-    for diff_count in &differences[1..n] {
-        if *diff_count != 1 {
-            return false;
+impl SidonIter {
+    /// Creates the search for Sidon sets of size `k` modulo `n`.
+    pub fn new(n: usize, k: usize) -> Self {
+        let mut core = DistinctDiffs::new(n / 2, n);
+        if k == 0 || n == 0 {
+            core.set.clear();
         }
+        Self { n, k, core }
     }
+}
+
+impl Iterator for SidonIter {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let (n, k) = (self.n, self.k);
+        self.core.search(k, |set, _| {
+            let t = set.len();
+            // elements still to be placed after the next one
+            let rest = k - 1 - t;
+            // the remaining gaps, closing one included, are distinct
+            let hi = n.saturating_sub((rest + 1) * (rest + 2) / 2);
+            if t == 1 {
+                // k gaps of at most a[1] each cover the whole circle
+                return ((n + 2 * k - 2) / k, hi);
+            }
+            // and the gaps after a[1] are all smaller than a[1]
+            let (first, last) = (set[1], set[t - 1]);
+            let lo = n.saturating_sub((rest + 1) * (first - 1));
+            (lo, hi.min(last + first - 1))
+        })
+    }
+}
+
+impl FusedIterator for SidonIter {}
+
+/// Generate modular Sidon sets
+///
+/// This generator produces the same sets as [`SidonIter`].
+///
+/// # Arguments
+///
+/// * `n` - The modulus
+/// * `k` - The size of each set
+///
+/// # Returns
+///
+/// A generator that yields the sets as sorted vectors starting with 0
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::{is_sidon_set, sidon_gen};
+///
+/// let sets: Vec<Vec<usize>> = sidon_gen(20, 4).into_iter().collect();
+/// assert!(!sets.is_empty());
+/// assert!(sets.iter().all(|set| is_sidon_set(set, 20)));
+/// // 5 * 4 > 20 - 1, so there is no room for a fifth element
+/// assert_eq!(sidon_gen(20, 5).into_iter().count(), 0);
+/// ```
+pub fn sidon_gen(n: usize, k: usize) -> GenBoxed<Vec<usize>> {
+    Gen::new_boxed(|co| async move {
+        for set in SidonIter::new(n, k) {
+            co.yield_(set).await;
+        }
+    })
+}
 
+/// Check if a set is a Sidon set modulo n
+///
+/// # Arguments
+///
+/// * `set` - The set to check
+/// * `n` - The modulus
+///
+/// # Returns
+///
+/// `true` if the differences `a - b` of distinct elements are distinct and
+/// non-zero modulo `n`, `false` otherwise
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::diffset::is_sidon_set;
+///
+/// assert!(is_sidon_set(&[0, 1, 3], 8));
+/// // 4 - 0 = 0 - 4 modulo 8
+/// assert!(!is_sidon_set(&[0, 1, 4], 8));
+/// ```
+pub fn is_sidon_set(set: &[usize], n: usize) -> bool {
+    if n == 0 {
+        return false;
+    }
+    let mut seen = vec![false; n];
+    for (i, &a) in set.iter().enumerate() {
+        for &b in &set[i + 1..] {
+            let diff = (a % n + n - b % n) % n;
+            if diff == 0 || 2 * diff == n || seen[diff] {
+                return false;
+            }
+            seen[diff] = true;
+            seen[n - diff] = true;
+        }
+    }
     true
 }
 
@@ -233,7 +1214,7 @@ mod tests {
     #[test]
     fn test_diffset_gen() {
         // Test that the generator finds at least one difference set
-        let gen = diffset_gen(13, 4, 2);
+        let gen = diffset_gen(13, 4, 2).unwrap();
         let mut sets: Vec<Vec<usize>> = Vec::new();
         for set in gen {
             sets.push(set);
@@ -248,28 +1229,285 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_diffset_iter() {
+        for (n, d) in [(7, 3), (13, 4), (31, 6)] {
+            let from_gen: Vec<Vec<usize>> = diffset_gen(n, d, 2).unwrap().into_iter().collect();
+            let mut iter = DiffsetIter::new(n, d, 2).unwrap();
+            let mut from_iter = Vec::new();
+            while let Some(set) = iter.next() {
+                // the search never holds more than one node per element
+                assert!(iter.stack.len() <= d);
+                from_iter.push(set);
+            }
+            assert_eq!(from_iter, from_gen);
+            // every difference added was undone
+            assert_eq!(iter.table.len(), 0);
+            assert!(iter.trail.is_empty());
+            assert_eq!(iter.next(), None);
+        }
+    }
+
     #[test]
     fn test_diffset_gen_invalid() {
         // Test with invalid parameters
-        let gen = diffset_gen(100, 5, 2);
-        let mut sets: Vec<Vec<usize>> = Vec::new();
-        for set in gen {
-            sets.push(set);
+        // No sets exist because n != d*(d-1)+1
+        assert_eq!(
+            diffset_gen(100, 5, 2).err(),
+            Some(DiffsetError::Infeasible {
+                n: 100,
+                d: 5,
+                lambda: 1
+            })
+        );
+        assert!(diffset_gen(12, 4, 2).is_err());
+        assert_eq!(
+            diffset_gen(1, 1, 2).err(),
+            Some(DiffsetError::InvalidSize { n: 1, d: 1 })
+        );
+        assert!(diffset_lambda_gen(15, 7, 2).is_err());
+        assert!(diffset_lambda_gen(7, 7, 6).is_err());
+        assert_eq!(
+            DiffsetError::Infeasible {
+                n: 15,
+                d: 7,
+                lambda: 2
+            }
+            .to_string(),
+            "no (15, 7, 2) difference set: d * (d - 1) must equal lambda * (n - 1)"
+        );
+    }
+
+    /// The smallest image of `set` under `x -> a * x + b` modulo `n` with `a` a
+    /// unit, which is the same for all sets in a class of difference sets.
+    fn affine_class(set: &[usize], n: usize) -> Vec<usize> {
+        (1..n)
+            .filter(|&a| (1..n).any(|b| a * b % n == 1))
+            .flat_map(|a| {
+                set.iter().map(move |&s| {
+                    let mut image: Vec<usize> =
+                        set.iter().map(|&x| a * ((x + n - s) % n) % n).collect();
+                    image.sort_unstable();
+                    image
+                })
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_is_diffset_lambda() {
+        assert!(is_diffset_lambda(&[1, 3, 4, 5, 9], 11, 2));
+        assert!(is_diffset_lambda(&[0, 1, 3, 9], 13, 1));
+        assert!(!is_diffset_lambda(&[0, 1, 3, 9], 13, 2));
+        assert!(!is_diffset_lambda(&[0, 1, 3, 9, 13], 13, 1));
+        // the complement of a (v, k, λ) difference set is a (v, v - k, v - 2k + λ) one
+        assert!(is_diffset_lambda(&[3, 6, 7, 9, 11, 12, 13, 14], 15, 4));
+        assert!(!is_diffset_lambda(&[], 7, 1));
+    }
+
+    #[test]
+    fn test_diffset_lambda_gen() {
+        // (v, k, λ, number of classes under multipliers and translations)
+        for (v, k, lambda, classes) in [
+            (7, 3, 1, 1),
+            (11, 5, 2, 1),
+            (13, 4, 1, 1),
+            (15, 7, 3, 1),
+            (19, 9, 4, 1),
+            (21, 5, 1, 1),
+            (23, 11, 5, 1),
+            (16, 6, 2, 0),
+        ] {
+            let sets: Vec<Vec<usize>> = diffset_lambda_gen(v, k, lambda)
+                .unwrap()
+                .into_iter()
+                .collect();
+            let mut found = BTreeSet::new();
+            for set in &sets {
+                assert!(is_diffset_lambda(set, v, lambda), "{set:?}");
+                found.insert(affine_class(set, v));
+            }
+            assert_eq!(found.len(), classes, "({v}, {k}, {lambda})");
         }
-        // Should not find any sets because n > d*(d-1)+1
-        assert!(sets.is_empty());
+        // the planar search finds the same sets up to equivalence
+        let planar: BTreeSet<Vec<usize>> = diffset_gen(21, 5, 2)
+            .unwrap()
+            .into_iter()
+            .map(|set| affine_class(&set, 21))
+            .collect();
+        let general: BTreeSet<Vec<usize>> = diffset_lambda_gen(21, 5, 1)
+            .unwrap()
+            .into_iter()
+            .map(|set| affine_class(&set, 21))
+            .collect();
+        assert_eq!(planar, general);
+    }
+
+    #[test]
+    fn test_diffset_lambda_iter() {
+        let mut iter = DiffsetLambdaIter::new(15, 7, 3).unwrap();
+        assert_eq!(iter.next(), Some(vec![0, 5, 6, 7, 9, 10, 13]));
+        let rest: Vec<Vec<usize>> = iter.by_ref().collect();
+        assert!(rest.iter().all(|set| is_diffset_lambda(set, 15, 3)));
+        // every difference was undone when its element was removed
+        assert_eq!(iter.set, [0]);
+        assert!(iter.count.iter().all(|&c| c == 0));
+        assert_eq!(iter.next(), None);
+        assert!(matches!(
+            DiffsetLambdaIter::new(16, 6, 3),
+            Err(DiffsetError::Infeasible { .. })
+        ));
     }
 
     #[test]
     fn test_diffset_gen_large() {
-        // Test with larger parameters (but still manageable)
-        let gen = diffset_gen(16, 4, 2);
-        let mut count = 0;
-        for _ in gen {
-            count += 1;
-        }
-        // Difference sets are rare, so we might not find any for these parameters
-        // The important thing is that the generator doesn't crash
-        assert!(count >= 0);
+        // Beyond the former limit of n = 70: the plane of order 8
+        let set = diffset_gen(73, 9, 2).unwrap().into_iter().next().unwrap();
+        assert!(is_diffset(&set, 73));
+    }
+
+    #[test]
+    fn test_singer_diffset() {
+        // the constructed planes agree with the search up to equivalence
+        for q in [2, 3, 4, 5, 7] {
+            let set = singer_diffset(q, 2).unwrap();
+            let n = q * q + q + 1;
+            // the planes of these orders are Desarguesian, so there is one class
+            let searched = diffset_gen(n, q + 1, 2)
+                .unwrap()
+                .into_iter()
+                .next()
+                .unwrap();
+            assert_eq!(affine_class(&set, n), affine_class(&searched, n), "q = {q}");
+        }
+        for q in [8, 9, 11, 13, 16, 17, 25, 27, 32] {
+            let set = singer_diffset(q, 2).unwrap();
+            assert!(is_diffset(&set, q * q + q + 1), "q = {q}");
+        }
+        // (q, m, v, k, λ)
+        for (q, m, v, k, lambda) in [
+            (2, 3, 15, 7, 3),
+            (2, 4, 31, 15, 7),
+            (2, 5, 63, 31, 15),
+            (3, 3, 40, 13, 4),
+            (4, 3, 85, 21, 5),
+            (3, 4, 121, 40, 13),
+        ] {
+            let set = singer_diffset(q, m).unwrap();
+            assert_eq!(set.len(), k);
+            assert!(is_diffset_lambda(&set, v, lambda), "q = {q}, m = {m}");
+        }
+        assert_eq!(
+            singer_diffset(6, 2),
+            Err(DiffsetError::NotPrimePower { q: 6 })
+        );
+        assert!(singer_diffset(0, 2).is_err());
+        assert!(singer_diffset(1, 2).is_err());
+        assert_eq!(
+            singer_diffset(5, 1),
+            Err(DiffsetError::InvalidSize { n: 6, d: 1 })
+        );
+    }
+
+    #[test]
+    fn test_paley_diffset() {
+        for p in [7, 11, 19, 23, 31, 43, 47, 59, 67, 71, 79, 83] {
+            let set = paley_diffset(p).unwrap();
+            assert_eq!(set.len(), (p - 1) / 2);
+            assert!(is_diffset_lambda(&set, p, (p - 3) / 4), "p = {p}");
+        }
+        // (7, 3, 1) is also a plane
+        assert!(is_diffset(&paley_diffset(7).unwrap(), 7));
+        for p in [0, 1, 2, 5, 13, 15, 27] {
+            assert_eq!(paley_diffset(p), Err(DiffsetError::NotPaleyPrime { p }));
+        }
+        assert_eq!(
+            paley_diffset(3),
+            Err(DiffsetError::InvalidSize { n: 3, d: 1 })
+        );
+    }
+
+    #[test]
+    fn test_optimal_golomb() {
+        // known optimal lengths for 1 to 10 marks
+        let lengths = [0, 1, 3, 6, 11, 17, 25, 34, 44, 55];
+        for (marks, &length) in (1..).zip(&lengths) {
+            let ruler = optimal_golomb(marks);
+            assert_eq!(ruler.len(), marks);
+            assert_eq!(ruler[marks - 1], length, "{marks} marks");
+            assert!(is_golomb_ruler(&ruler));
+        }
+        assert!(optimal_golomb(0).is_empty());
+        // too slow to prove optimal here, but a ruler of that length exists
+        let ruler = GolombIter::new(11, 72).next().unwrap();
+        assert!(is_golomb_ruler(&ruler));
+    }
+
+    #[test]
+    fn test_golomb_iter() {
+        // (marks, optimal length, number of optimal rulers up to reflection)
+        for (marks, length, count) in [(3, 3, 1), (4, 6, 1), (5, 11, 2), (6, 17, 4), (7, 25, 5)] {
+            assert_eq!(GolombIter::new(marks, length - 1).count(), 0);
+            let rulers: Vec<Vec<usize>> = GolombIter::new(marks, length).collect();
+            assert_eq!(rulers.len(), count, "{marks} marks");
+            assert!(rulers.windows(2).all(|w| w[0] < w[1]));
+            for ruler in &rulers {
+                assert!(is_golomb_ruler(ruler));
+                let mirror: Vec<usize> = ruler.iter().rev().map(|&a| length - a).collect();
+                assert!(!rulers.contains(&mirror));
+            }
+        }
+        assert_eq!(GolombIter::new(1, 0).collect::<Vec<_>>(), [[0]]);
+        assert_eq!(GolombIter::new(2, 3).collect::<Vec<_>>(), [[0, 3]]);
+        assert_eq!(GolombIter::new(0, 0).count(), 0);
+        assert_eq!(GolombIter::new(2, 0).count(), 0);
+        let from_gen: Vec<Vec<usize>> = golomb_gen(6, 19).into_iter().collect();
+        assert_eq!(from_gen, GolombIter::new(6, 19).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sidon_iter() {
+        // compare with one representative per translation class, by brute force
+        for n in 1..16 {
+            for k in 1..6 {
+                let mut expected = BTreeSet::new();
+                for mask in 0u32..1 << n {
+                    let set: Vec<usize> = (0..n).filter(|&i| mask >> i & 1 == 1).collect();
+                    if set.len() == k && is_sidon_set(&set, n) {
+                        expected.insert(canonical_translate(&set, n));
+                    }
+                }
+                let sets: Vec<Vec<usize>> = SidonIter::new(n, k).collect();
+                assert!(sets.windows(2).all(|w| w[0] < w[1]));
+                let classes: BTreeSet<Vec<usize>> = sets
+                    .iter()
+                    .map(|set| {
+                        assert!(is_sidon_set(set, n), "{set:?} modulo {n}");
+                        canonical_translate(set, n)
+                    })
+                    .collect();
+                assert_eq!(classes.len(), sets.len());
+                assert_eq!(classes, expected, "n = {n}, k = {k}");
+            }
+        }
+        // the largest Sidon sets modulo 31 are its planar difference sets
+        let sets: Vec<Vec<usize>> = sidon_gen(31, 6).into_iter().collect();
+        assert!(!sets.is_empty());
+        assert!(sets.iter().all(|set| is_diffset(set, 31)));
+        assert_eq!(SidonIter::new(31, 7).count(), 0);
+        assert_eq!(SidonIter::new(0, 1).count(), 0);
+    }
+
+    /// The smallest translate of `set` modulo `n` that contains 0.
+    fn canonical_translate(set: &[usize], n: usize) -> Vec<usize> {
+        set.iter()
+            .map(|&s| {
+                let mut image: Vec<usize> = set.iter().map(|&x| (x + n - s) % n).collect();
+                image.sort_unstable();
+                image
+            })
+            .min()
+            .unwrap()
     }
 }
@@ -6,6 +6,7 @@
 //! ## Key Functions
 //!
 //! - [`brgc_gen`] - Generate binary reflected Gray code sequence
+//! - [`BrgcIter`] - Loopless, allocation-free iterator over the same sequence
 //!
 //! ## Algorithm
 //!
@@ -17,16 +18,105 @@
 //! This produces a sequence of 2^n code words where each successive word differs
 //! by exactly one bit.
 //!
+//! Unrolled, the bit flipped at step `i` (counting from 1) is the number of
+//! trailing zeros of `i`, which is what [`BrgcIter`] computes in O(1) per step.
+//!
 //! ## Reference
 //!
 //! Gray codes were originally designed by Frank Gray (1953) for pulse code modulation.
 //!
 //! ## Complexity
 //!
-//! - `brgc_gen(n)`: O(2^n) output size, O(n) stack space
+//! - `brgc_gen(n)`: O(2^n) output size, a single boxed generator wrapping [`BrgcIter`]
+//! - `BrgcIter::new(n)`: O(1) worst case per step, no heap allocation
 
+use core::iter::FusedIterator;
+#[cfg(feature = "generator")]
 use genawaiter::sync::{Gen, GenBoxed};
 
+/// Loopless binary reflected Gray code iterator
+///
+/// `BrgcIter` yields the same bit positions as [`brgc_gen`], i.e. the position of
+/// the bit to flip at each of the `2^n - 1` steps, in O(1) worst-case time per
+/// step and without heap allocation.
+///
+/// # Panics
+///
+/// [`BrgcIter::new`] panics if `n >= 128`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::gray_code::BrgcIter;
+///
+/// let flips: Vec<usize> = BrgcIter::new(3).collect();
+/// assert_eq!(flips, [0, 1, 0, 2, 0, 1, 0]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "BrgcState")
+)]
+pub struct BrgcIter {
+    cnt: u128,
+    last: u128,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BrgcState {
+    cnt: u128,
+    last: u128,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BrgcState> for BrgcIter {
+    type Error = &'static str;
+
+    fn try_from(state: BrgcState) -> Result<Self, Self::Error> {
+        let BrgcState { cnt, last } = state;
+        // `last` is `2^n - 1` for some `n < 128`
+        if last == u128::MAX || last & (last + 1) != 0 || cnt > last {
+            return Err("invalid BrgcIter state");
+        }
+        Ok(Self { cnt, last })
+    }
+}
+
+impl BrgcIter {
+    /// Creates the iterator for Gray codes of `n` bits.
+    pub fn new(n: usize) -> Self {
+        assert!(n < 128, "n must be less than 128");
+        Self {
+            cnt: 0,
+            last: (1u128 << n) - 1,
+        }
+    }
+}
+
+impl Iterator for BrgcIter {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        if self.cnt == self.last {
+            return None;
+        }
+        self.cnt += 1;
+        Some(self.cnt.trailing_zeros() as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.last - self.cnt) {
+            Ok(r) => (r, Some(r)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl FusedIterator for BrgcIter {}
+
 /// The `brgc_gen` function generates a binary reflexed gray code sequence of length `n`.
 ///
 /// Arguments:
@@ -54,16 +144,10 @@ use genawaiter::sync::{Gen, GenBoxed};
 ///
 /// assert_eq!(cnt, 8);
 /// ```
+#[cfg(feature = "generator")]
 pub fn brgc_gen(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| async move {
-        if n < 1 {
-            return;
-        }
-        for i in brgc_gen(n - 1) {
-            co.yield_(i).await;
-        }
-        co.yield_(n - 1).await;
-        for i in brgc_gen(n - 1) {
+        for i in BrgcIter::new(n) {
             co.yield_(i).await;
         }
     })
@@ -73,6 +157,7 @@ pub fn brgc_gen(n: usize) -> GenBoxed<usize> {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_brgc() {
         let mut cnt = 1;
@@ -81,4 +166,32 @@ mod tests {
         }
         assert_eq!(cnt, 8);
     }
+
+    #[test]
+    fn test_brgc_iter_matches_recursion() {
+        fn brgc_recur(n: usize, out: &mut Vec<usize>) {
+            if n < 1 {
+                return;
+            }
+            brgc_recur(n - 1, out);
+            out.push(n - 1);
+            brgc_recur(n - 1, out);
+        }
+        for n in 0..12 {
+            let mut expected = Vec::new();
+            brgc_recur(n, &mut expected);
+            assert_eq!(BrgcIter::new(n).collect::<Vec<_>>(), expected);
+            #[cfg(feature = "generator")]
+            assert_eq!(brgc_gen(n).into_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_brgc_iter_size_hint() {
+        let mut iter = BrgcIter::new(10);
+        assert_eq!(iter.size_hint(), (1023, Some(1023)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (1022, Some(1022)));
+        assert_eq!(BrgcIter::new(100).size_hint(), (usize::MAX, None));
+    }
 }
@@ -0,0 +1,503 @@
+//! Reading and writing generated sequences
+//!
+//! This module streams the items of any generator - moves such as the
+//! `(usize, usize)` swaps of [`EmkCombIter`](crate::EmkCombIter), or whole
+//! objects as `Vec<usize>` - to a file, and reads them back for replay, e.g.
+//! to compare against a regression golden.
+//!
+//! Three [`Format`]s are supported, all without a header:
+//!
+//! - [`Format::JsonLines`] - one JSON value per line: a bare number for
+//!   single-field items, an array of numbers otherwise
+//! - [`Format::Csv`] - one line per item with the fields separated by commas
+//! - [`Format::Binary`] - each field as a little-endian `u32`; items of
+//!   variable length (`Vec<usize>`) are preceded by their length, also as a
+//!   little-endian `u32`
+//!
+//! Any type implementing [`Record`] can be written and read; implementations
+//! are provided for `usize`, `(usize, usize)`, `(usize, usize, usize)` and
+//! `Vec<usize>`. With the `serde` feature, the iterators and state types of the
+//! crate can in addition be serialized themselves, so that a long enumeration
+//! can be suspended and resumed. Deserializing first reads the saved fields on
+//! their own and checks them against each other, so that a state the
+//! enumeration could not have reached, e.g. one edited by hand, is rejected
+//! with an error instead of making the iterator panic later on.
+//!
+//! # Examples
+//!
+//! ```
+//! use ecgen::io::{read_records, write_records, Format};
+//! use ecgen::EmkCombIter;
+//!
+//! let mut golden = Vec::new();
+//! write_records(&mut golden, Format::Csv, EmkCombIter::new(5, 2)).unwrap();
+//! assert!(golden.starts_with(b"1,2\n"));
+//!
+//! let moves: Vec<(usize, usize)> = read_records(&golden[..], Format::Csv)
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert!(moves.into_iter().eq(EmkCombIter::new(5, 2)));
+//! ```
+
+use std::io::{self, BufRead, Write};
+use std::marker::PhantomData;
+
+/// Encoding of a record stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// One JSON number or array of numbers per line.
+    JsonLines,
+    /// One line of comma-separated numbers per record.
+    Csv,
+    /// Little-endian `u32` fields, with a length prefix for variable-length records.
+    Binary,
+}
+
+/// An item that can be written as a list of numbers
+pub trait Record: Sized {
+    /// The number of fields of every record, or `None` if it varies.
+    const ARITY: Option<usize>;
+
+    /// Appends the fields of the record to `buf`.
+    fn push_fields(&self, buf: &mut Vec<usize>);
+
+    /// Rebuilds a record from its fields, or returns `None` if they do not fit.
+    fn from_fields(fields: &[usize]) -> Option<Self>;
+}
+
+impl Record for usize {
+    const ARITY: Option<usize> = Some(1);
+
+    fn push_fields(&self, buf: &mut Vec<usize>) {
+        buf.push(*self);
+    }
+
+    fn from_fields(fields: &[usize]) -> Option<Self> {
+        match *fields {
+            [x] => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl Record for (usize, usize) {
+    const ARITY: Option<usize> = Some(2);
+
+    fn push_fields(&self, buf: &mut Vec<usize>) {
+        buf.extend([self.0, self.1]);
+    }
+
+    fn from_fields(fields: &[usize]) -> Option<Self> {
+        match *fields {
+            [x, y] => Some((x, y)),
+            _ => None,
+        }
+    }
+}
+
+impl Record for (usize, usize, usize) {
+    const ARITY: Option<usize> = Some(3);
+
+    fn push_fields(&self, buf: &mut Vec<usize>) {
+        buf.extend([self.0, self.1, self.2]);
+    }
+
+    fn from_fields(fields: &[usize]) -> Option<Self> {
+        match *fields {
+            [x, y, z] => Some((x, y, z)),
+            _ => None,
+        }
+    }
+}
+
+impl Record for Vec<usize> {
+    const ARITY: Option<usize> = None;
+
+    fn push_fields(&self, buf: &mut Vec<usize>) {
+        buf.extend_from_slice(self);
+    }
+
+    fn from_fields(fields: &[usize]) -> Option<Self> {
+        Some(fields.to_vec())
+    }
+}
+
+fn to_u32(x: usize) -> io::Result<u32> {
+    u32::try_from(x).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("field {x} does not fit the binary format"),
+        )
+    })
+}
+
+/// Writer that encodes records one at a time
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::io::{Format, RecordWriter};
+///
+/// let mut writer = RecordWriter::new(Vec::new(), Format::JsonLines);
+/// writer.write(&vec![0, 2, 1]).unwrap();
+/// writer.write(&vec![2, 0, 1]).unwrap();
+/// assert_eq!(writer.count(), 2);
+/// assert_eq!(writer.into_inner().unwrap(), b"[0,2,1]\n[2,0,1]\n");
+/// ```
+#[derive(Debug)]
+pub struct RecordWriter<W: Write> {
+    inner: W,
+    format: Format,
+    buf: Vec<usize>,
+    count: u64,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// Creates a writer that encodes records into `inner` in the given format.
+    ///
+    /// The writer issues many small writes, so `inner` should be buffered
+    /// when it is a file or a socket.
+    pub fn new(inner: W, format: Format) -> Self {
+        Self {
+            inner,
+            format,
+            buf: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Encodes one record.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error of the underlying writer, or an error of kind
+    /// [`io::ErrorKind::InvalidInput`] if a field exceeds `u32::MAX` in the
+    /// binary format.
+    pub fn write<R: Record>(&mut self, record: &R) -> io::Result<()> {
+        self.buf.clear();
+        record.push_fields(&mut self.buf);
+        let out = &mut self.inner;
+        match self.format {
+            Format::Binary => {
+                if R::ARITY.is_none() {
+                    out.write_all(&to_u32(self.buf.len())?.to_le_bytes())?;
+                }
+                for &x in &self.buf {
+                    out.write_all(&to_u32(x)?.to_le_bytes())?;
+                }
+            }
+            Format::JsonLines | Format::Csv => {
+                let bracket = self.format == Format::JsonLines && R::ARITY != Some(1);
+                if bracket {
+                    out.write_all(b"[")?;
+                }
+                for (i, x) in self.buf.iter().enumerate() {
+                    if i > 0 {
+                        out.write_all(b",")?;
+                    }
+                    write!(out, "{x}")?;
+                }
+                if bracket {
+                    out.write_all(b"]")?;
+                }
+                out.write_all(b"\n")?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// The number of records written so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Flushes the underlying writer and returns it.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// The `write_records` function writes every item of a generator in the given format.
+///
+/// Arguments:
+///
+/// * `writer`: The destination, e.g. a `BufWriter<File>` or a `Vec<u8>`.
+/// * `format`: The encoding of the records.
+/// * `records`: The items to write, e.g. a move iterator such as `SjtIter::new(5)`.
+///
+/// Returns:
+///
+/// The function `write_records` returns the number of records written.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::io::{write_records, Format};
+/// use ecgen::BrgcIter;
+///
+/// let mut out = Vec::new();
+/// assert_eq!(write_records(&mut out, Format::Binary, BrgcIter::new(3)).unwrap(), 7);
+/// assert_eq!(out.len(), 7 * 4);
+/// ```
+///
+/// # Errors
+///
+/// Returns the first error reported by [`RecordWriter::write`] or by the final flush.
+pub fn write_records<W, I>(writer: W, format: Format, records: I) -> io::Result<u64>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: Record,
+{
+    let mut writer = RecordWriter::new(writer, format);
+    for record in records {
+        writer.write(&record)?;
+    }
+    let count = writer.count();
+    writer.into_inner()?;
+    Ok(count)
+}
+
+/// Iterator over the records decoded from a reader
+///
+/// Created by [`read_records`]. Each item is either a record or the error that
+/// stopped decoding; malformed input is reported with
+/// [`io::ErrorKind::InvalidData`] and the line number for text formats.
+#[derive(Debug)]
+pub struct RecordReader<R, T> {
+    inner: R,
+    format: Format,
+    line: String,
+    line_no: usize,
+    fields: Vec<usize>,
+    failed: bool,
+    _record: PhantomData<fn() -> T>,
+}
+
+/// The `read_records` function decodes the records written by [`write_records`].
+///
+/// Arguments:
+///
+/// * `reader`: The source, e.g. a `BufReader<File>` or a byte slice.
+/// * `format`: The encoding of the records.
+///
+/// Returns:
+///
+/// The function `read_records` returns a [`RecordReader`] yielding each record
+/// in turn; it stops after the first error.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::io::{read_records, Format};
+///
+/// let input = "[0,1,2]\n\n[1,0,2]\n";
+/// let perms: Vec<Vec<usize>> = read_records(input.as_bytes(), Format::JsonLines)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(perms, [[0, 1, 2], [1, 0, 2]]);
+/// ```
+pub fn read_records<R: BufRead, T: Record>(reader: R, format: Format) -> RecordReader<R, T> {
+    RecordReader {
+        inner: reader,
+        format,
+        line: String::new(),
+        line_no: 0,
+        fields: Vec::new(),
+        failed: false,
+        _record: PhantomData,
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl<R: BufRead, T: Record> RecordReader<R, T> {
+    /// Reads one little-endian `u32`; `Ok(None)` at a clean end of input.
+    fn read_u32(&mut self, at_start: bool) -> io::Result<Option<usize>> {
+        let mut bytes = [0u8; 4];
+        if at_start && self.inner.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        self.inner.read_exact(&mut bytes)?;
+        Ok(Some(u32::from_le_bytes(bytes) as usize))
+    }
+
+    fn read_binary(&mut self) -> io::Result<Option<T>> {
+        let len = match T::ARITY {
+            Some(len) => len,
+            None => match self.read_u32(true)? {
+                Some(len) => len,
+                None => return Ok(None),
+            },
+        };
+        self.fields.clear();
+        for i in 0..len {
+            match self.read_u32(i == 0 && T::ARITY.is_some())? {
+                Some(x) => self.fields.push(x),
+                None => return Ok(None),
+            }
+        }
+        T::from_fields(&self.fields)
+            .map(Some)
+            .ok_or_else(|| invalid_data(format!("invalid record {:?}", self.fields)))
+    }
+
+    fn read_text(&mut self) -> io::Result<Option<T>> {
+        loop {
+            self.line.clear();
+            if self.inner.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+            self.line_no += 1;
+            let mut text = self.line.trim();
+            if self.format == Format::JsonLines {
+                if text.is_empty() {
+                    continue;
+                }
+                if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+                    text = inner.trim();
+                }
+            }
+            self.fields.clear();
+            if !text.is_empty() {
+                for field in text.split(',') {
+                    let x = field.trim().parse().map_err(|_| {
+                        invalid_data(format!(
+                            "line {}: invalid field `{}`",
+                            self.line_no,
+                            field.trim()
+                        ))
+                    })?;
+                    self.fields.push(x);
+                }
+            }
+            return T::from_fields(&self.fields).map(Some).ok_or_else(|| {
+                invalid_data(format!(
+                    "line {}: expected {} fields, found {}",
+                    self.line_no,
+                    T::ARITY.unwrap_or(0),
+                    self.fields.len()
+                ))
+            });
+        }
+    }
+}
+
+impl<R: BufRead, T: Record> Iterator for RecordReader<R, T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<io::Result<T>> {
+        if self.failed {
+            return None;
+        }
+        let result = match self.format {
+            Format::Binary => self.read_binary(),
+            Format::JsonLines | Format::Csv => self.read_text(),
+        };
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmkCombIter, SetPartitionIter, SjtIter};
+
+    const FORMATS: [Format; 3] = [Format::JsonLines, Format::Csv, Format::Binary];
+
+    fn roundtrip<T: Record + Clone + PartialEq + core::fmt::Debug>(records: &[T]) {
+        for format in FORMATS {
+            let mut out = Vec::new();
+            let count = write_records(&mut out, format, records.iter().cloned()).unwrap();
+            assert_eq!(count, records.len() as u64);
+            let back: Vec<T> = read_records(&out[..], format)
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(back, records, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip(&SjtIter::new(4).collect::<Vec<_>>());
+        roundtrip(&EmkCombIter::new(7, 3).collect::<Vec<_>>());
+        roundtrip(&[(0, 5, 2), (3, 1, 0)]);
+        roundtrip(&[vec![2, 0, 1], vec![], vec![7]]);
+        roundtrip::<usize>(&[]);
+    }
+
+    #[test]
+    fn test_text_layout() {
+        let mut out = Vec::new();
+        write_records(
+            &mut out,
+            Format::JsonLines,
+            SetPartitionIter::new(4, 2).take(2),
+        )
+        .unwrap();
+        assert_eq!(out, b"[3,1]\n[2,1]\n");
+        out.clear();
+        write_records(&mut out, Format::JsonLines, [3usize, 1]).unwrap();
+        assert_eq!(out, b"3\n1\n");
+        out.clear();
+        write_records(&mut out, Format::Binary, [vec![1, 258]]).unwrap();
+        assert_eq!(out, [2, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let mut reader = read_records::<_, (usize, usize)>(&b"1,2\n3\n4,5\n"[..], Format::Csv);
+        assert_eq!(reader.next().unwrap().unwrap(), (1, 2));
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2"));
+        assert!(reader.next().is_none());
+
+        let mut reader = read_records::<_, usize>(&b"[1]\nx\n"[..], Format::JsonLines);
+        assert_eq!(reader.next().unwrap().unwrap(), 1);
+        assert!(reader.next().unwrap().is_err());
+
+        let mut reader = read_records::<_, (usize, usize)>(&[1, 0, 0, 0, 2][..], Format::Binary);
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let err = write_records(&mut Vec::new(), Format::Binary, [usize::MAX]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_resume() {
+        use crate::Combination;
+
+        let mut comb = Combination::chase(8, 3);
+        for _ in 0..10 {
+            comb.advance();
+        }
+        let saved = serde_json::to_string(&comb).unwrap();
+        let mut resumed: Combination = serde_json::from_str(&saved).unwrap();
+        while let Some(c) = comb.advance() {
+            let indices = c.indices().to_vec();
+            assert_eq!(resumed.advance().unwrap().indices(), indices);
+        }
+        assert!(resumed.advance().is_none());
+
+        // states the enumeration cannot reach are rejected rather than panicking later
+        let chase = r#"{"a":[],"w":[],"r":0,"remaining":null,"done":false}"#;
+        assert!(serde_json::from_str::<crate::ChaseIter>(chase).is_err());
+        let mut value = serde_json::to_value(Combination::chase(8, 3)).unwrap();
+        value["indices"][0] = 7.into();
+        assert!(serde_json::from_value::<Combination>(value).is_err());
+        let mut parts = crate::SetPartition::new(5, 3);
+        parts.advance();
+        let mut value = serde_json::to_value(&parts).unwrap();
+        value["rgs"][4] = 3.into();
+        assert!(serde_json::from_value::<crate::SetPartition>(value).is_err());
+    }
+}
@@ -6,7 +6,30 @@
 //! ## Features
 //!
 //! - `std` (default): Enables standard library support and logging
+//! - `alloc`: Enables the iterators and helpers that need a heap, without `std`
+//! - `generator` (default): Enables the `genawaiter`-based `*_gen` functions
+//!   (requires `std`)
 //! - `diffset`: Enables difference set generation (optional)
+//! - `bigint`: Enables the exact `*_big` counting functions backed by `num-bigint`
+//! - `rayon`: Enables the parallel enumerations of the [`par`] module
+//! - `serde`: Derives `Serialize` and `Deserialize` for the iterators and state
+//!   types, so that an enumeration can be saved and resumed; a saved state that
+//!   the enumeration could not have reached is rejected when it is deserialized
+//!
+//! Every generator is also available as a plain [`Iterator`] (e.g. [`SjtIter`],
+//! [`EmkCombIter`]) that runs the recursion on an explicit stack, which is
+//! considerably faster in hot loops than the boxed generators.
+//!
+//! ## `no_std`
+//!
+//! Without the `std` feature the crate is `#![no_std]`. The counting functions
+//! and [`BrgcIter`] need neither `std` nor a heap; enable the `alloc` feature for
+//! the remaining iterators, [`Combination`] and the ranking functions:
+//!
+//! ```toml
+//! [dependencies]
+//! ecgen-rs = { version = "0.1", default-features = false, features = ["alloc"] }
+//! ```
 //!
 //! ## Logging
 //!
@@ -24,23 +47,89 @@
 //! RUST_LOG=debug cargo run --features std
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod combin;
+pub mod counting;
 #[cfg(feature = "diffset")]
 pub mod diffset;
 pub mod gray_code;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod multiset;
+// rayon itself needs a newer toolchain than the rest of the crate
+#[cfg(feature = "rayon")]
+#[clippy::msrv = "1.73"]
+pub mod par;
 pub mod perm;
+pub mod restricted;
 pub mod set_bipart;
 pub mod set_partition;
+#[cfg(feature = "alloc")]
+pub mod verify;
 
-pub use crate::combin::{comb, emk_comb_gen};
+pub use crate::combin::{checked_comb, checked_comb_u128, comb, CombOrder};
+pub use crate::counting::{
+    bell, catalan, checked_bell, checked_bell_u128, checked_catalan, checked_derangement,
+    checked_involution, checked_lah, checked_multinomial, checked_stirling1st,
+    checked_stirling1st_signed, derangement, involution, lah, multinomial, stirling1st,
+    stirling1st_signed,
+};
 #[cfg(feature = "diffset")]
-pub use crate::diffset::{diffset_gen, is_diffset};
+pub use crate::diffset::{
+    diffset_gen, diffset_lambda_gen, golomb_gen, is_diffset, is_diffset_lambda, is_golomb_ruler,
+    is_sidon_set, optimal_golomb, paley_diffset, sidon_gen, singer_diffset, DiffsetError,
+    DiffsetIter, DiffsetLambdaIter, GolombIter, SidonIter,
+};
+pub use crate::gray_code::BrgcIter;
+pub use crate::perm::{checked_factorial, checked_factorial_u128, factorial, PermOrder};
+pub use crate::restricted::{checked_cycle_type_count, cycle_type_count};
+pub use crate::set_bipart::{checked_stirling2nd2, checked_stirling2nd2_u128, stirling2nd2};
+pub use crate::set_partition::{checked_stirling2nd, checked_stirling2nd_u128, stirling2nd};
+
+#[cfg(feature = "bigint")]
+pub use crate::combin::comb_big;
+#[cfg(feature = "bigint")]
+pub use crate::perm::factorial_big;
+#[cfg(feature = "bigint")]
+pub use crate::set_bipart::stirling2nd2_big;
+#[cfg(feature = "bigint")]
+pub use crate::set_partition::stirling2nd_big;
+#[cfg(feature = "bigint")]
+pub use num_bigint::BigUint;
+
+#[cfg(feature = "alloc")]
+pub use crate::combin::{rank_comb, unrank_comb, ChaseIter, Combination, EmkCombIter, LiuTangIter};
+#[cfg(feature = "alloc")]
+pub use crate::multiset::{MultisetIter, MultisetPermutations};
+#[cfg(feature = "alloc")]
+pub use crate::perm::{
+    rank_perm, unrank_perm, EhrIter, EvenIter, HeapIter, LexIter, Permutations, SjtIter,
+};
+#[cfg(feature = "alloc")]
+pub use crate::restricted::{CyclePermutations, Derangements};
+#[cfg(feature = "alloc")]
+pub use crate::set_bipart::SetBipartIter;
+#[cfg(feature = "alloc")]
+pub use crate::set_partition::{SetPartition, SetPartitionAllIter, SetPartitionIter};
+
+#[cfg(feature = "generator")]
+pub use crate::combin::emk_comb_gen;
+#[cfg(feature = "generator")]
 pub use crate::gray_code::brgc_gen;
-pub use crate::perm::{ehr_gen, factorial, sjt_gen};
-pub use crate::set_bipart::{set_bipart_gen, stirling2nd2};
-pub use crate::set_partition::{set_partition_gen, stirling2nd};
+#[cfg(feature = "generator")]
+pub use crate::multiset::multiset_gen;
+#[cfg(feature = "generator")]
+pub use crate::perm::{ehr_gen, sjt_gen};
+#[cfg(feature = "generator")]
+pub use crate::set_bipart::set_bipart_gen;
+#[cfg(feature = "generator")]
+pub use crate::set_partition::{set_partition_all_gen, set_partition_gen};
 
-#[cfg(test)]
+#[cfg(all(test, feature = "generator"))]
 mod tests {
     use super::*;
 
@@ -1,17 +1,619 @@
-use genawaiter::stack::{let_gen_using, Co};
+//! `ecgen` - generate combinatorial objects from the command line
+//!
+//! Each subcommand prints one object (or, with `--moves`, one move) per line,
+//! so the output can be piped into other tools:
+//!
+//! ```text
+//! ecgen perm 3 --algo ehr
+//! ecgen comb 5 2 --moves --format csv
+//! ecgen partition 10 4 --count
+//! ```
+//!
+//! Run `ecgen --help` for the full list of subcommands and options.
 
-async fn producer(co: Co<'_, i32>) {
-    let mut n = 1;
-    while n < 10 {
-        co.yield_(n).await;
-        n += 2;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+
+use ecgen::combin::{unrank_comb, Combination};
+use ecgen::perm::{unrank_perm, Permutations};
+use ecgen::set_partition::SetPartition;
+use ecgen::{
+    checked_bell_u128, checked_comb_u128, checked_factorial_u128, checked_stirling2nd2_u128,
+    checked_stirling2nd_u128, BrgcIter, CombOrder, PermOrder, SetBipartIter,
+};
+
+const USAGE: &str = "\
+Usage: ecgen <COMMAND> [OPTIONS]
+
+Commands:
+  gray N                 Binary reflected Gray code of N bits
+  perm N                 Permutations of 0..N
+  comb N K               K-combinations of 0..N
+  partition N [K]        Partitions of 0..N into K blocks (all partitions if K is omitted)
+  bipart N               Partitions of 0..N into two blocks
+  diffset N D            Difference sets of size D modulo N (needs the `diffset` feature)
+
+Options:
+  --algo ALGO            perm: sjt (default), ehr, heap, even or lex
+  --order ORDER          comb: emk (default), chase, liu-tang, cool-lex or colex
+  --threshold T          diffset: depth from which the search is pruned (default 2)
+  --lambda L             diffset: number of times each difference occurs (default 1)
+  --moves                Print the move leading to each object instead of the objects
+  --count                Print only the number of lines that would be printed
+  --limit L              Print at most L lines
+  --rank-range A..B      Print only the lines numbered A to B - 1 (counting from 0);
+                         the objects of gray, perm (sjt, even, lex) and comb (emk, colex)
+                         are unranked directly, all other lines are generated from line 0
+  --format FORMAT        text (default), csv or json
+  -h, --help             Print this help
+
+Objects and moves are printed as lists of numbers, with elements counted from 0:
+  gray: the bits             moves: the bit flipped
+  perm: the permutation      moves: the two positions swapped (not for lex)
+  comb: the sorted subset    moves: the pairs of positions exchanged
+  partition, bipart: the block of each element (restricted growth string)
+                             moves: element, old block, new block
+  diffset: the set";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PermAlgo {
+    Sjt,
+    Ehr,
+    Heap,
+    Even,
+    Lex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CombAlgo {
+    Emk,
+    Chase,
+    LiuTang,
+    CoolLex,
+    Colex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Gray(usize),
+    Perm(usize, PermAlgo),
+    Comb(usize, usize, CombAlgo),
+    Partition(usize, Option<usize>),
+    Bipart(usize),
+    Diffset(DiffsetArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DiffsetArgs {
+    n: usize,
+    d: usize,
+    threshold: usize,
+    lambda: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    command: Command,
+    moves: bool,
+    count: bool,
+    limit: Option<usize>,
+    range: Option<(usize, usize)>,
+    format: Format,
+}
+
+fn parse_number(s: &str, what: &str) -> Result<usize, String> {
+    s.parse()
+        .map_err(|_| format!("invalid {what} `{s}`: expected a non-negative integer"))
+}
+
+fn parse_range(s: &str) -> Result<(usize, usize), String> {
+    let (a, b) = s
+        .split_once("..")
+        .ok_or_else(|| format!("invalid rank range `{s}`: expected A..B"))?;
+    let (a, b) = (parse_number(a, "rank")?, parse_number(b, "rank")?);
+    if a > b {
+        return Err(format!("invalid rank range `{s}`: {a} is greater than {b}"));
+    }
+    Ok((a, b))
+}
+
+/// Parses the command line (without the program name); `Ok(None)` asks for help.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut positional = Vec::new();
+    let mut algo = None;
+    let mut order = None;
+    let mut threshold = None;
+    let mut lambda = None;
+    let mut moves = false;
+    let mut count = false;
+    let mut limit = None;
+    let mut range = None;
+    let mut format = Format::Text;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| format!("option `{name}` needs a value"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--moves" => moves = true,
+            "--count" => count = true,
+            "--algo" => algo = Some(value("--algo")?),
+            "--order" => order = Some(value("--order")?),
+            "--lambda" => lambda = Some(parse_number(&value("--lambda")?, "lambda")?),
+            "--threshold" => threshold = Some(parse_number(&value("--threshold")?, "threshold")?),
+            "--limit" => limit = Some(parse_number(&value("--limit")?, "limit")?),
+            "--rank-range" => range = Some(parse_range(&value("--rank-range")?)?),
+            "--format" => {
+                format = match value("--format")?.as_str() {
+                    "text" => Format::Text,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    other => return Err(format!("unknown format `{other}`")),
+                }
+            }
+            s if s.starts_with('-') => return Err(format!("unknown option `{s}`")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let (name, params) = positional
+        .split_first()
+        .ok_or_else(|| "missing command".to_string())?;
+    let params = params
+        .iter()
+        .map(|p| parse_number(p, "argument"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let arity = |expected: &[usize]| {
+        if expected.contains(&params.len()) {
+            Ok(())
+        } else {
+            Err(format!("wrong number of arguments for `{name}`"))
+        }
+    };
+    let command = match name.as_str() {
+        "gray" => {
+            arity(&[1])?;
+            if params[0] >= 128 {
+                return Err("N must be less than 128".to_string());
+            }
+            Command::Gray(params[0])
+        }
+        "perm" => {
+            arity(&[1])?;
+            let algo = match algo.as_deref().unwrap_or("sjt") {
+                "sjt" => PermAlgo::Sjt,
+                "ehr" => PermAlgo::Ehr,
+                "heap" => PermAlgo::Heap,
+                "even" => PermAlgo::Even,
+                "lex" => PermAlgo::Lex,
+                other => return Err(format!("unknown permutation algorithm `{other}`")),
+            };
+            Command::Perm(params[0], algo)
+        }
+        "comb" => {
+            arity(&[2])?;
+            if params[1] > params[0] {
+                return Err("K must not exceed N".to_string());
+            }
+            let order = match order.as_deref().unwrap_or("emk") {
+                "emk" => CombAlgo::Emk,
+                "chase" => CombAlgo::Chase,
+                "liu-tang" => CombAlgo::LiuTang,
+                "cool-lex" => CombAlgo::CoolLex,
+                "colex" => CombAlgo::Colex,
+                other => return Err(format!("unknown combination order `{other}`")),
+            };
+            Command::Comb(params[0], params[1], order)
+        }
+        "partition" => {
+            arity(&[1, 2])?;
+            let k = params.get(1).copied();
+            if let Some(k) = k {
+                if k > params[0] || (k == 0 && params[0] > 0) {
+                    return Err("K must be in 1..=N".to_string());
+                }
+            }
+            Command::Partition(params[0], k)
+        }
+        "bipart" => {
+            arity(&[1])?;
+            Command::Bipart(params[0])
+        }
+        "diffset" => {
+            arity(&[2])?;
+            if params[1] < 2 {
+                return Err("D must be at least 2".to_string());
+            }
+            Command::Diffset(DiffsetArgs {
+                n: params[0],
+                d: params[1],
+                threshold: threshold.unwrap_or(2),
+                lambda: lambda.unwrap_or(1),
+            })
+        }
+        other => return Err(format!("unknown command `{other}`")),
+    };
+    if moves && matches!(command, Command::Diffset(..)) {
+        return Err("`diffset` has no moves".to_string());
+    }
+    if moves && matches!(command, Command::Perm(_, PermAlgo::Lex)) {
+        // a lex step also reverses a suffix, so one swap does not replay it
+        return Err("`perm --algo lex` has no moves".to_string());
+    }
+    Ok(Some(Options {
+        command,
+        moves,
+        count,
+        limit,
+        range,
+        format,
+    }))
+}
+
+type Rows = Box<dyn Iterator<Item = Vec<usize>>>;
+
+/// The lines to print for `command`: the objects, or the moves between them.
+fn rows(command: Command, moves: bool) -> Result<Rows, String> {
+    Ok(match command {
+        Command::Gray(n) => {
+            if moves {
+                Box::new(BrgcIter::new(n).map(|i| vec![i]))
+            } else {
+                let mut bits = vec![0; n];
+                let first = Some(bits.clone());
+                Box::new(first.into_iter().chain(BrgcIter::new(n).map(move |i| {
+                    bits[i] ^= 1;
+                    bits.clone()
+                })))
+            }
+        }
+        Command::Perm(n, algo) => {
+            let items: Vec<usize> = (0..n).collect();
+            let mut perms = match algo {
+                PermAlgo::Sjt => Permutations::sjt(items),
+                PermAlgo::Ehr => Permutations::ehr(items),
+                PermAlgo::Heap => Permutations::heap(items),
+                PermAlgo::Even => Permutations::even(items),
+                PermAlgo::Lex => Permutations::lex(items),
+            };
+            Box::new(
+                std::iter::from_fn(move || {
+                    let (p, swap) = perms.advance_with_swap()?;
+                    Some(match swap {
+                        Some((i, j)) if moves => Some(vec![i, j]),
+                        _ if moves => None,
+                        _ => Some(p.to_vec()),
+                    })
+                })
+                .flatten(),
+            )
+        }
+        Command::Comb(n, k, order) => {
+            let mut combs = match order {
+                CombAlgo::Emk => Combination::new(n, k),
+                CombAlgo::Chase => Combination::chase(n, k),
+                CombAlgo::LiuTang => Combination::liu_tang(n, k),
+                CombAlgo::CoolLex => Combination::cool_lex(n, k),
+                CombAlgo::Colex => Combination::colex(n, k),
+            };
+            let mut first = true;
+            Box::new(
+                std::iter::from_fn(move || {
+                    let c = combs.advance()?;
+                    let row = if !moves {
+                        Some(c.indices().to_vec())
+                    } else if std::mem::take(&mut first) {
+                        None
+                    } else {
+                        Some(c.last_swaps().iter().flat_map(|&(i, j)| [i, j]).collect())
+                    };
+                    Some(row)
+                })
+                .flatten(),
+            )
+        }
+        Command::Partition(n, k) => {
+            let mut parts = match k {
+                Some(k) => SetPartition::new(n, k),
+                None => SetPartition::all(n),
+            };
+            Box::new(
+                std::iter::from_fn(move || {
+                    let p = parts.advance()?;
+                    Some(match p.last_move() {
+                        Some((x, from, to)) if moves => Some(vec![x, from, to]),
+                        _ if moves => None,
+                        _ => Some(p.rgs().to_vec()),
+                    })
+                })
+                .flatten(),
+            )
+        }
+        Command::Bipart(n) => {
+            if n < 2 {
+                return Ok(Box::new(std::iter::empty()));
+            }
+            let mut rgs: Vec<usize> = (0..n).map(|i| usize::from(i + 1 == n)).collect();
+            let first = (!moves).then(|| rgs.clone());
+            Box::new(first.into_iter().chain(SetBipartIter::new(n).map(move |x| {
+                let from = rgs[x - 1];
+                rgs[x - 1] = 1 - from;
+                if moves {
+                    vec![x - 1, from, 1 - from]
+                } else {
+                    rgs.clone()
+                }
+            })))
+        }
+        Command::Diffset(args) => diffset_rows(args)?,
+    })
+}
+
+#[cfg(feature = "diffset")]
+fn diffset_rows(args: DiffsetArgs) -> Result<Rows, String> {
+    use ecgen::diffset::{diffset_gen, DiffsetLambdaIter};
+
+    let DiffsetArgs {
+        n,
+        d,
+        threshold,
+        lambda,
+    } = args;
+    let err = |e: ecgen::diffset::DiffsetError| e.to_string();
+    Ok(if lambda == 1 {
+        Box::new(diffset_gen(n, d, threshold).map_err(err)?.into_iter())
+    } else {
+        Box::new(DiffsetLambdaIter::new(n, d, lambda).map_err(err)?)
+    })
+}
+
+#[cfg(not(feature = "diffset"))]
+fn diffset_rows(_args: DiffsetArgs) -> Result<Rows, String> {
+    Err("`diffset` requires building with the `diffset` feature".to_string())
+}
+
+/// The objects of `command` with ranks `start..end`, each found by unranking,
+/// if the order of `command` has a ranking.
+fn ranked_rows(command: Command, start: usize, end: usize) -> Option<Rows> {
+    let end = match known_count(command) {
+        Some(Some(total)) => end.min(usize::try_from(total).unwrap_or(usize::MAX)),
+        _ => end,
+    };
+    let ranks = start.min(end)..end;
+    Some(match command {
+        Command::Gray(n) => Box::new(ranks.map(move |r| {
+            let code = r ^ (r >> 1);
+            (0..n)
+                .map(|i| code.checked_shr(i as u32).unwrap_or(0) & 1)
+                .collect()
+        })),
+        Command::Perm(n, PermAlgo::Sjt | PermAlgo::Even) => {
+            Box::new(ranks.map(move |r| unrank_perm(r, n, PermOrder::Sjt)))
+        }
+        Command::Perm(n, PermAlgo::Lex) => {
+            Box::new(ranks.map(move |r| unrank_perm(r, n, PermOrder::Lex)))
+        }
+        Command::Comb(n, k, CombAlgo::Emk) => {
+            Box::new(ranks.map(move |r| unrank_comb(r, n, k, CombOrder::Emk)))
+        }
+        Command::Comb(n, k, CombAlgo::Colex) => {
+            Box::new(ranks.map(move |r| unrank_comb(r, n, k, CombOrder::Colex)))
+        }
+        _ => return None,
+    })
+}
+
+/// The number of objects of `command`, if it is known without enumerating
+/// them; `Some(None)` if it is known to be too large to count.
+fn known_count(command: Command) -> Option<Option<u128>> {
+    Some(match command {
+        Command::Gray(n) => 1u128.checked_shl(n as u32),
+        Command::Perm(n, _) => checked_factorial_u128(n as u128),
+        Command::Comb(n, k, _) => checked_comb_u128(n as u128, k as u128),
+        Command::Partition(n, Some(k)) => checked_stirling2nd_u128(n as u128, k as u128),
+        Command::Partition(n, None) => checked_bell_u128(n as u128),
+        Command::Bipart(n) => checked_stirling2nd2_u128(n as u128),
+        Command::Diffset(..) => return None,
+    })
+}
+
+fn write_row(out: &mut impl Write, row: &[usize], format: Format) -> io::Result<()> {
+    let (open, sep, close) = match format {
+        Format::Text => ("", " ", ""),
+        Format::Csv => ("", ",", ""),
+        Format::Json => ("[", ",", "]"),
+    };
+    write!(out, "{open}")?;
+    for (i, x) in row.iter().enumerate() {
+        if i > 0 {
+            write!(out, "{sep}")?;
+        }
+        write!(out, "{x}")?;
     }
+    writeln!(out, "{close}")
 }
 
-fn main() {
-    let_gen_using!(odds_under_ten, producer);
+fn run(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let (start, end) = options.range.unwrap_or((0, usize::MAX));
+    let len = end - start;
+    let len = options.limit.map_or(len, |l| l.min(len));
+    if options.count {
+        if let Some(total) = known_count(options.command) {
+            let bounded = options.range.is_some() || options.limit.is_some();
+            let c = match total {
+                // one move fewer than objects
+                Some(total) => {
+                    let c = total
+                        .saturating_sub(u128::from(options.moves))
+                        .saturating_sub(start as u128);
+                    if bounded {
+                        c.min(len as u128)
+                    } else {
+                        c
+                    }
+                }
+                // more than u128::MAX lines, so the range and limit are full
+                None if bounded => len as u128,
+                None => return Err("the number of lines is too large to count".to_string()),
+            };
+            return writeln!(out, "{c}").map_err(|e| e.to_string());
+        }
+    }
+    let ranked = match options.range {
+        Some((start, end)) if !options.moves => ranked_rows(options.command, start, end),
+        _ => None,
+    };
+    let rows: Rows = match ranked {
+        Some(rows) => rows,
+        None => Box::new(rows(options.command, options.moves)?.skip(start)),
+    };
+    let mut rows = rows.take(len);
+    let result = if options.count {
+        writeln!(out, "{}", rows.count())
+    } else {
+        rows.try_for_each(|row| write_row(out, &row, options.format))
+    };
+    result.and_then(|()| out.flush()).or_else(|e| {
+        // stop quietly when the reader of a pipe goes away
+        if e.kind() == io::ErrorKind::BrokenPipe {
+            Ok(())
+        } else {
+            Err(e.to_string())
+        }
+    })
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    match run(&options, &mut out) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(args: &str) -> String {
+        let args = args.split_whitespace().map(String::from);
+        let options = parse_args(args).unwrap().unwrap();
+        let mut out = Vec::new();
+        run(&options, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(["--help".to_string()]), Ok(None));
+        assert!(parse_args(Vec::new()).is_err());
+        assert!(parse_args(["comb", "3", "4"].map(String::from)).is_err());
+        assert!(parse_args(["perm", "3", "--algo", "foo"].map(String::from)).is_err());
+        assert!(parse_args(["gray", "3", "--rank-range", "5..2"].map(String::from)).is_err());
+        assert!(parse_args(["gray", "128"].map(String::from)).is_err());
+        assert!(parse_args(["perm", "4", "--algo", "lex", "--moves"].map(String::from)).is_err());
+        let options = parse_args(["perm", "4", "--algo", "ehr", "--limit", "3"].map(String::from))
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.command, Command::Perm(4, PermAlgo::Ehr));
+        assert_eq!(options.limit, Some(3));
+    }
+
+    #[test]
+    fn test_objects_and_moves() {
+        assert_eq!(
+            output("perm 3"),
+            "0 1 2\n0 2 1\n2 0 1\n2 1 0\n1 2 0\n1 0 2\n"
+        );
+        assert_eq!(output("perm 3 --moves"), "1 2\n0 1\n1 2\n0 1\n1 2\n");
+        assert_eq!(output("comb 3 2 --format json"), "[0,1]\n[0,2]\n[1,2]\n");
+        assert_eq!(output("gray 2 --format csv"), "0,0\n1,0\n1,1\n0,1\n");
+        assert_eq!(output("bipart 3"), "0 0 1\n0 1 1\n0 1 0\n");
+        assert_eq!(output("partition 3 --moves").lines().count(), 4);
+    }
+
+    #[test]
+    fn test_count_limit_range() {
+        assert_eq!(output("comb 40 8 --count"), "76904685\n");
+        assert_eq!(output("partition 6 --count"), "203\n");
+        assert_eq!(output("perm 4 --moves --count"), "23\n");
+        assert_eq!(output("gray 10 --limit 5 --count"), "5\n");
+        assert_eq!(output("perm 3 --rank-range 2..4"), "2 0 1\n2 1 0\n");
+        assert_eq!(output("perm 3 --rank-range 2..4 --limit 1"), "2 0 1\n");
+        // counted without enumerating, even beyond usize
+        assert_eq!(output("perm 25 --count"), "15511210043330985984000000\n");
+        assert_eq!(output("perm 4 --rank-range 20..30 --count"), "4\n");
+        assert_eq!(output("perm 4 --moves --rank-range 20..30 --count"), "3\n");
+        assert_eq!(output("perm 40 --rank-range 5..9 --count"), "4\n");
+        assert_eq!(
+            output("partition 40 --count"),
+            "157450588391204931289324344702531067\n"
+        );
+        let options = parse_args(["partition", "120", "--count"].map(String::from))
+            .unwrap()
+            .unwrap();
+        assert!(run(&options, &mut Vec::new()).is_err());
+    }
 
-    for num in odds_under_ten {
-        println!("{}", num);
+    #[test]
+    fn test_rank_range_unranks() {
+        for args in [
+            "gray 5",
+            "perm 5",
+            "perm 5 --algo even",
+            "perm 5 --algo lex",
+            "comb 7 3",
+            "comb 7 3 --order colex",
+            "comb 7 3 --order chase",
+            "partition 6 3",
+        ] {
+            let all = output(args);
+            let all: Vec<&str> = all.lines().collect();
+            for (a, b) in [(0, 3), (7, 20), (30, 200), (500, 600)] {
+                let some = output(&format!("{args} --rank-range {a}..{b}"));
+                let expected = &all[a.min(all.len())..b.min(all.len())];
+                assert_eq!(
+                    some.lines().collect::<Vec<_>>(),
+                    expected,
+                    "{args} {a}..{b}"
+                );
+            }
+        }
+        // far beyond what could be skipped through
+        let line = output("perm 20 --rank-range 1000000000000000000..1000000000000000001");
+        let perm: Vec<usize> = line
+            .split_whitespace()
+            .map(|x| x.parse().unwrap())
+            .collect();
+        assert_eq!(
+            ecgen::perm::rank_perm(&perm, PermOrder::Sjt),
+            1_000_000_000_000_000_000
+        );
+        assert_eq!(output("perm 25 --rank-range 0..5").lines().count(), 5);
     }
 }
@@ -0,0 +1,518 @@
+//! Multiset permutation generation
+//!
+//! This module generates the distinct arrangements of a multiset such as
+//! `{a, a, b, c, c, c}`, which [`crate::perm`] would list with duplicates.
+//!
+//! ## Algorithm
+//!
+//! The arrangements are listed in cool-lex order: each one is obtained from the
+//! previous one by a *prefix shift*, i.e. by moving the element at some position
+//! `p` to the front. Kept as a linked list, the arrangement needs a constant
+//! number of pointers to find each shift, so [`MultisetIter`] is loopless.
+//!
+//! The first arrangement lists the elements in non-increasing order, and
+//! shifting the whole list (`p = n - 1`) after the last arrangement returns to
+//! the first, so the order is cyclic.
+//!
+//! The number of arrangements is the multinomial coefficient
+//! [`multinomial`](crate::counting::multinomial) of the multiplicities.
+//!
+//! ## Reference
+//!
+//! A. Williams, "Loopless generation of multiset permutations using a constant
+//! number of variables by prefix shifts", SODA 2009.
+//!
+//! ## Complexity
+//!
+//! - `MultisetIter::new(ks)`: O(1) worst-case time per shift, O(n) space
+//! - `MultisetPermutations`: O(p) time to apply a shift at position `p`
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::iter::FusedIterator;
+#[cfg(feature = "generator")]
+use genawaiter::sync::{Gen, GenBoxed};
+
+#[cfg(feature = "alloc")]
+use crate::counting::checked_multinomial;
+
+#[cfg(feature = "alloc")]
+const NIL: usize = usize::MAX;
+
+/// Loopless iterator over the prefix shifts of cool-lex order for multisets
+///
+/// The multiset consists of `ks[e]` copies of each element `e`. Starting from
+/// the elements in non-increasing order, each item `p` means moving the element
+/// at position `p` to the front, i.e. `a[..=p].rotate_right(1)`. All
+/// `multinomial(ks) - 1` shifts are yielded, so every distinct arrangement is
+/// visited exactly once.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::multiset::MultisetIter;
+///
+/// let mut a = [1, 1, 0];
+/// let mut seen = vec![a];
+/// for p in MultisetIter::new(&[1, 2]) {
+///     a[..=p].rotate_right(1);
+///     seen.push(a);
+/// }
+///
+/// assert_eq!(seen, [[1, 1, 0], [0, 1, 1], [1, 0, 1]]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "MultisetState")
+)]
+pub struct MultisetIter {
+    value: Vec<usize>,
+    next: Vec<usize>,
+    head: usize,
+    i: usize,
+    j: usize,
+    pos_i: usize,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct MultisetState {
+    value: Vec<usize>,
+    next: Vec<usize>,
+    head: usize,
+    i: usize,
+    j: usize,
+    pos_i: usize,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<MultisetState> for MultisetIter {
+    type Error = &'static str;
+
+    fn try_from(state: MultisetState) -> Result<Self, Self::Error> {
+        let MultisetState {
+            value,
+            next,
+            head,
+            i,
+            j,
+            pos_i,
+            remaining,
+            done,
+        } = state;
+        let iter = Self {
+            value,
+            next,
+            head,
+            i,
+            j,
+            pos_i,
+            remaining,
+            done,
+        };
+        // the list of an empty multiset still has its end marker
+        let sized = iter.next.len() == iter.value.len().max(1);
+        if !sized || !(iter.done || iter.is_reachable()) {
+            return Err("invalid MultisetIter state");
+        }
+        Ok(iter)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl MultisetIter {
+    /// Creates the iterator for the multiset with `ks[e]` copies of element `e`.
+    ///
+    /// The iterator is empty if the multiset has fewer than two elements.
+    pub fn new(ks: &[usize]) -> Self {
+        let value: Vec<usize> = ks
+            .iter()
+            .enumerate()
+            .rev()
+            .flat_map(|(e, &k)| core::iter::repeat(e).take(k))
+            .collect();
+        let n = value.len();
+        let next = (1..n).chain(Some(NIL)).collect();
+        Self {
+            value,
+            next,
+            head: 0,
+            i: n.saturating_sub(2),
+            j: n.saturating_sub(1),
+            pos_i: n.saturating_sub(2),
+            remaining: checked_multinomial(ks).map(|r| r - 1),
+            done: n < 2,
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl MultisetIter {
+    /// The number of elements of the multiset.
+    pub(crate) fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Whether all shifts have been yielded.
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// The elements in their current order, following the list from `head`.
+    pub(crate) fn arrangement(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut node = self.head;
+        core::iter::from_fn(move || {
+            let e = *self.value.get(node)?;
+            node = self.next[node];
+            Some(e)
+        })
+        .take(self.value.len())
+    }
+
+    /// Whether the list holds every element exactly once and the cursors are
+    /// where the shifts put them for this arrangement: `i` is the last node of
+    /// the longest non-increasing prefix, but never the last node of the list.
+    fn is_reachable(&self) -> bool {
+        let n = self.value.len();
+        let mut order = Vec::with_capacity(n);
+        let mut seen = vec![false; n];
+        let mut node = self.head;
+        while node != NIL {
+            if node >= n || core::mem::replace(&mut seen[node], true) {
+                return false;
+            }
+            order.push(node);
+            node = self.next[node];
+        }
+        if n < 2 || order.len() != n {
+            return false;
+        }
+        let prefix = 1
+            + (1..n)
+                .take_while(|&p| self.value[order[p]] <= self.value[order[p - 1]])
+                .count();
+        self.pos_i == (prefix - 1).min(n - 2)
+            && self.i == order[self.pos_i]
+            && self.j == self.next[self.i]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for MultisetIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.done {
+            return None;
+        }
+        let (value, next) = (&self.value, &mut self.next);
+        if next[self.j] == NIL && value[self.j] >= value[self.head] {
+            self.done = true;
+            return None;
+        }
+        let (s, p) = if next[self.j] != NIL && value[self.i] >= value[next[self.j]] {
+            (self.j, self.pos_i + 2)
+        } else {
+            (self.i, self.pos_i + 1)
+        };
+        let t = next[s];
+        next[s] = next[t];
+        next[t] = self.head;
+        if value[t] < value[self.head] {
+            self.i = t;
+            self.pos_i = 0;
+        } else {
+            self.pos_i += 1;
+        }
+        self.j = next[self.i];
+        self.head = t;
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some(p)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for MultisetIter {}
+
+/// The `multiset_gen` function generates the prefix shifts of cool-lex order for a multiset.
+///
+/// Arguments:
+///
+/// * `ks`: The multiplicities: the multiset has `ks[e]` copies of element `e`.
+///
+/// Returns:
+///
+/// The function `multiset_gen` returns a `GenBoxed<usize>` that yields the same
+/// positions as [`MultisetIter`].
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::{multinomial, multiset_gen};
+///
+/// let mut a = ["c", "c", "c", "b", "a", "a"];
+/// let mut cnt = 1;
+/// for p in multiset_gen(&[2, 1, 3]) {
+///     a[..=p].rotate_right(1);
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, multinomial(&[2, 1, 3]));
+/// ```
+#[cfg(feature = "generator")]
+pub fn multiset_gen(ks: &[usize]) -> GenBoxed<usize> {
+    let iter = MultisetIter::new(ks);
+    Gen::new_boxed(|co| async move {
+        for p in iter {
+            co.yield_(p).await;
+        }
+    })
+}
+
+/// Stateful permutation of a multiset of items
+///
+/// `MultisetPermutations` owns a list of items, some of which may be equal, and
+/// visits each distinct arrangement exactly once in cool-lex order, starting
+/// with the items sorted in non-increasing order. Successive arrangements
+/// differ by moving one item to the front.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::multiset::MultisetPermutations;
+///
+/// let mut perms = MultisetPermutations::new(vec!['a', 'b', 'a']);
+/// let mut seen = Vec::new();
+/// while let Some(p) = perms.advance() {
+///     seen.push(p.iter().collect::<String>());
+/// }
+///
+/// assert_eq!(seen, ["baa", "aba", "aab"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "MultisetPermutationsState<T>",
+        bound(deserialize = "T: serde::Deserialize<'de> + Ord")
+    )
+)]
+pub struct MultisetPermutations<T> {
+    items: Vec<T>,
+    shifts: MultisetIter,
+    last_shift: Option<usize>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct MultisetPermutationsState<T> {
+    items: Vec<T>,
+    shifts: MultisetIter,
+    last_shift: Option<usize>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl<T: Ord> TryFrom<MultisetPermutationsState<T>> for MultisetPermutations<T> {
+    type Error = &'static str;
+
+    fn try_from(state: MultisetPermutationsState<T>) -> Result<Self, Self::Error> {
+        let MultisetPermutationsState {
+            items,
+            shifts,
+            last_shift,
+            started,
+        } = state;
+        let n = items.len();
+        // equal items must be equal elements, larger items larger elements
+        let mut pairs: Vec<(usize, &T)> = shifts.arrangement().zip(&items).collect();
+        pairs.sort_unstable_by_key(|&(e, _)| e);
+        let ordered = pairs
+            .windows(2)
+            .all(|w| (w[0].0 == w[1].0) == (w[0].1 == w[1].1) && w[0].1 <= w[1].1);
+        let valid = shifts.len() == n
+            && last_shift.map_or(true, |p| p < n)
+            && (shifts.is_done() || ordered);
+        if !valid {
+            return Err("invalid MultisetPermutations state");
+        }
+        Ok(Self {
+            items,
+            shifts,
+            last_shift,
+            started,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> MultisetPermutations<T> {
+    /// Creates the distinct arrangements of `items`.
+    pub fn new(items: impl Into<Vec<T>>) -> Self {
+        let mut items = items.into();
+        items.sort_unstable_by(|a, b| b.cmp(a));
+        let mut ks = Vec::new();
+        for (idx, item) in items.iter().enumerate() {
+            if idx == 0 || items[idx - 1] != *item {
+                ks.push(0);
+            }
+            *ks.last_mut().unwrap() += 1;
+        }
+        ks.reverse();
+        Self {
+            items,
+            shifts: MultisetIter::new(&ks),
+            last_shift: None,
+            started: false,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> MultisetPermutations<T> {
+    /// Moves to the next arrangement and returns a view of it.
+    ///
+    /// The first call returns the items in non-increasing order; `None` is
+    /// returned once all distinct arrangements have been visited.
+    pub fn advance(&mut self) -> Option<&[T]> {
+        self.advance_with_shift().map(|(items, _)| items)
+    }
+
+    /// Like [`MultisetPermutations::advance`], but also returns the position
+    /// `p` whose item was moved to the front (`None` for the first arrangement).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::multiset::MultisetPermutations;
+    ///
+    /// let mut perms = MultisetPermutations::new([0, 1, 1]);
+    /// assert_eq!(perms.advance_with_shift(), Some((&[1, 1, 0][..], None)));
+    /// assert_eq!(perms.advance_with_shift(), Some((&[0, 1, 1][..], Some(2))));
+    /// ```
+    pub fn advance_with_shift(&mut self) -> Option<(&[T], Option<usize>)> {
+        if !self.started {
+            self.started = true;
+            return Some((&self.items, None));
+        }
+        let p = self.shifts.next()?;
+        self.items[..=p].rotate_right(1);
+        self.last_shift = Some(p);
+        Some((&self.items, self.last_shift))
+    }
+
+    /// Returns the current arrangement.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns the position of the item moved to the front by the last call
+    /// to [`MultisetPermutations::advance`], if any.
+    pub fn last_shift(&self) -> Option<usize> {
+        self.last_shift
+    }
+
+    /// Consumes the state and returns the items in their current order.
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::counting::multinomial;
+    use std::collections::HashSet;
+
+    fn arrangements(ks: &[usize]) -> Vec<Vec<usize>> {
+        let mut a: Vec<usize> = ks
+            .iter()
+            .enumerate()
+            .rev()
+            .flat_map(|(e, &k)| core::iter::repeat(e).take(k))
+            .collect();
+        let mut out = vec![a.clone()];
+        for p in MultisetIter::new(ks) {
+            a[..=p].rotate_right(1);
+            out.push(a.clone());
+        }
+        out
+    }
+
+    #[test]
+    fn test_multiset_iter_visits_all() {
+        for ks in [
+            &[][..],
+            &[1],
+            &[2],
+            &[1, 1],
+            &[2, 1, 3],
+            &[1, 1, 1, 1],
+            &[3, 2],
+            &[0, 2, 0, 1],
+            &[1, 3, 2, 1],
+        ] {
+            let all = arrangements(ks);
+            assert_eq!(all.len(), multinomial(ks));
+            let distinct: HashSet<_> = all.iter().collect();
+            assert_eq!(distinct.len(), all.len());
+            // cyclic: shifting the whole list returns to the start
+            if all.len() > 1 {
+                let mut last = all[all.len() - 1].clone();
+                last.rotate_right(1);
+                assert_eq!(last, all[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiset_iter_size_hint() {
+        let mut iter = MultisetIter::new(&[2, 1, 3]);
+        assert_eq!(iter.size_hint(), (59, Some(59)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (58, Some(58)));
+        assert_eq!(iter.count(), 58);
+        assert_eq!(MultisetIter::new(&[1; 30]).size_hint(), (usize::MAX, None));
+    }
+
+    #[test]
+    fn test_multiset_permutations() {
+        let mut perms = MultisetPermutations::new(vec!["a", "c", "b", "c", "a", "c"]);
+        let mut seen = HashSet::new();
+        while let Some(p) = perms.advance() {
+            assert!(seen.insert(p.concat()));
+        }
+        assert_eq!(seen.len(), 60);
+        assert_eq!(perms.last_shift(), Some(4));
+        let mut items = perms.into_inner();
+        items.rotate_right(1);
+        assert_eq!(items, ["c", "c", "c", "b", "a", "a"]);
+    }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_multiset_gen() {
+        let shifts: Vec<usize> = multiset_gen(&[1, 1, 1]).into_iter().collect();
+        assert_eq!(shifts, MultisetIter::new(&[1, 1, 1]).collect::<Vec<_>>());
+    }
+}
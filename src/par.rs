@@ -0,0 +1,361 @@
+//! Parallel enumeration with rayon
+//!
+//! The functions in this module split the rank space `0..N` of an enumeration
+//! into contiguous chunks. Each rayon worker unranks the first object of a
+//! chunk and then steps through the chunk with an in-place successor function
+//! that does not allocate, so no worker ever walks the objects before its
+//! chunk. A step rewrites only the positions after the one that changes.
+//!
+//! All objects are listed in lexicographic order, which is the order in which
+//! `collect::<Vec<_>>()` returns them:
+//!
+//! - [`par_combinations`] - k-combinations of `0..n` as sorted index lists, in
+//!   the order of [`unrank_comb`] with [`CombOrder::Lex`]
+//! - [`par_permutations`] - permutations of `0..n`, in the order of
+//!   [`unrank_perm`] with [`PermOrder::Lex`]
+//! - [`par_set_partitions`] - partitions of `0..n` into `k` blocks as
+//!   restricted growth strings, in lexicographic order
+//!
+//! # Examples
+//!
+//! ```
+//! use ecgen::par::par_combinations;
+//! use rayon::prelude::*;
+//!
+//! // number of 8-subsets of 0..24 whose sum is divisible by 8
+//! let hits = par_combinations(24, 8)
+//!     .filter(|c| c.iter().sum::<usize>() % 8 == 0)
+//!     .count();
+//! assert_eq!(hits, 91_998);
+//! ```
+
+use rayon::prelude::*;
+
+use crate::combin::{comb, unrank_comb, CombOrder};
+use crate::perm::{factorial, unrank_perm, PermOrder};
+use crate::set_partition::stirling2nd;
+
+/// Lists the objects of ranks `0..total` in parallel: `first(r)` unranks the
+/// start of a chunk into a stepping state and `step` moves the state to its
+/// successor in place.
+fn par_ranks<T, F, S>(total: usize, first: F, step: S) -> impl ParallelIterator<Item = Vec<usize>>
+where
+    T: AsRef<[usize]>,
+    F: Fn(usize) -> T + Sync + Send,
+    S: Fn(&mut T) + Sync + Send + Copy,
+{
+    // a few chunks per thread keeps the workers busy when chunks are uneven
+    let chunk = (total / (rayon::current_num_threads() * 8)).max(1);
+    let chunks = total.div_ceil(chunk);
+    (0..chunks).into_par_iter().flat_map_iter(move |c| {
+        let start = c * chunk;
+        let end = total.min(start + chunk);
+        let mut cur = first(start);
+        (start..end).map(move |r| {
+            let obj = cur.as_ref().to_vec();
+            if r + 1 < end {
+                step(&mut cur);
+            }
+            obj
+        })
+    })
+}
+
+/// Moves a sorted k-combination of `0..n` to its lexicographic successor.
+fn next_comb_lex(c: &mut [usize], n: usize) {
+    let k = c.len();
+    if let Some(i) = (0..k).rev().find(|&i| c[i] < n - k + i) {
+        c[i] += 1;
+        for j in i + 1..k {
+            c[j] = c[j - 1] + 1;
+        }
+    }
+}
+
+/// Moves a permutation to its lexicographic successor.
+fn next_perm_lex(p: &mut [usize]) {
+    let n = p.len();
+    if let Some(i) = (0..n.saturating_sub(1)).rev().find(|&i| p[i] < p[i + 1]) {
+        let j = (i + 1..n).rev().find(|&j| p[j] > p[i]).unwrap();
+        p.swap(i, j);
+        p[i + 1..].reverse();
+    }
+}
+
+/// `table[i][m]` is the number of ways to complete a restricted growth string
+/// of length `n` with exactly `k` blocks whose first `i` entries have maximum `m`.
+///
+/// Only the prefixes that exist, i.e. `m < i`, are filled in; each of their
+/// entries is at most `stirling2nd(n, k)`.
+fn rgs_table(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut table = vec![vec![0; k + 1]; n + 1];
+    if k > 0 {
+        table[n][k - 1] = 1;
+    }
+    for i in (1..n).rev() {
+        for m in 0..k.min(i) {
+            let stay = (m + 1) * table[i + 1][m];
+            table[i][m] = if m + 1 < k {
+                stay + table[i + 1][m + 1]
+            } else {
+                stay
+            };
+        }
+    }
+    table
+}
+
+/// The restricted growth string of rank `r` in lexicographic order.
+fn unrank_rgs(mut r: usize, n: usize, table: &[Vec<usize>]) -> Vec<usize> {
+    let mut rgs = vec![0; n];
+    let mut m = 0;
+    for i in 1..n {
+        let stay = table[i + 1][m];
+        if r < (m + 1) * stay {
+            rgs[i] = r / stay;
+            r %= stay;
+        } else {
+            r -= (m + 1) * stay;
+            m += 1;
+            rgs[i] = m;
+        }
+    }
+    rgs
+}
+
+/// A restricted growth string with the running maxima of its prefixes.
+struct Rgs {
+    a: Vec<usize>,
+    /// `prefix_max[i]` is the largest label among `a[..i]`.
+    prefix_max: Vec<usize>,
+}
+
+impl Rgs {
+    fn new(a: Vec<usize>) -> Self {
+        let mut prefix_max = vec![0; a.len()];
+        for i in 1..a.len() {
+            prefix_max[i] = prefix_max[i - 1].max(a[i - 1]);
+        }
+        Self { a, prefix_max }
+    }
+}
+
+impl AsRef<[usize]> for Rgs {
+    fn as_ref(&self) -> &[usize] {
+        &self.a
+    }
+}
+
+/// Moves a restricted growth string with exactly `k` blocks to its
+/// lexicographic successor.
+fn next_rgs(rgs: &mut Rgs, k: usize) {
+    let Rgs { a, prefix_max } = rgs;
+    let n = a.len();
+    for i in (1..n).rev() {
+        let v = a[i] + 1;
+        let m = prefix_max[i].max(v);
+        // the new label must be allowed, and the suffix must still fit the
+        // blocks that have not been used yet
+        if v <= prefix_max[i] + 1 && v < k && k - 1 - m <= n - 1 - i {
+            a[i] = v;
+            let fresh = k - 1 - m;
+            for j in i + 1..n {
+                let from_end = n - j;
+                a[j] = if from_end <= fresh { k - from_end } else { 0 };
+                prefix_max[j] = prefix_max[j - 1].max(a[j - 1]);
+            }
+            return;
+        }
+    }
+}
+
+/// The `par_combinations` function enumerates the k-combinations of `0..n` in parallel.
+///
+/// Arguments:
+///
+/// * `n`: The parameter `n` represents the total number of elements in the set.
+/// * `k`: The parameter `k` represents the number of elements in each combination.
+///
+/// Returns:
+///
+/// The function `par_combinations` returns a `ParallelIterator` over the
+/// combinations as sorted `Vec`s of `k` indices, in lexicographic order.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::par::par_combinations;
+/// use rayon::prelude::*;
+///
+/// let all: Vec<Vec<usize>> = par_combinations(4, 2).collect();
+/// assert_eq!(all, [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `k > n` or if `comb(n, k)` overflows `usize`.
+pub fn par_combinations(n: usize, k: usize) -> impl ParallelIterator<Item = Vec<usize>> {
+    assert!(k <= n, "k must not exceed n");
+    par_ranks(
+        comb(n, k),
+        move |r| unrank_comb(r, n, k, CombOrder::Lex),
+        move |c: &mut Vec<usize>| next_comb_lex(c, n),
+    )
+}
+
+/// The `par_permutations` function enumerates the permutations of `0..n` in parallel.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+///
+/// Returns:
+///
+/// The function `par_permutations` returns a `ParallelIterator` over the
+/// permutations in lexicographic order.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::par::par_permutations;
+/// use rayon::prelude::*;
+///
+/// // number of permutations of 0..9 without a fixed point
+/// let derangements = par_permutations(9)
+///     .filter(|p| p.iter().enumerate().all(|(i, &x)| i != x))
+///     .count();
+/// assert_eq!(derangements, 133_496);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `factorial(n)` overflows `usize`.
+pub fn par_permutations(n: usize) -> impl ParallelIterator<Item = Vec<usize>> {
+    par_ranks(
+        factorial(n),
+        move |r| unrank_perm(r, n, PermOrder::Lex),
+        |p: &mut Vec<usize>| next_perm_lex(p),
+    )
+}
+
+/// The `par_set_partitions` function enumerates the partitions of `0..n` into `k` blocks in parallel.
+///
+/// Arguments:
+///
+/// * `n`: The parameter `n` represents the total number of elements in the set.
+/// * `k`: The parameter `k` represents the number of blocks.
+///
+/// Returns:
+///
+/// The function `par_set_partitions` returns a `ParallelIterator` over the
+/// partitions as restricted growth strings (entry `x` is the block of element
+/// `x`, and blocks are numbered in order of their smallest element), in
+/// lexicographic order.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::par::par_set_partitions;
+/// use rayon::prelude::*;
+///
+/// let all: Vec<Vec<usize>> = par_set_partitions(4, 3).collect();
+/// assert_eq!(
+///     all,
+///     [[0, 0, 1, 2], [0, 1, 0, 2], [0, 1, 1, 2], [0, 1, 2, 0], [0, 1, 2, 1], [0, 1, 2, 2]]
+/// );
+/// ```
+///
+/// # Panics
+///
+/// Panics if `stirling2nd(n, k)` overflows `usize`.
+pub fn par_set_partitions(n: usize, k: usize) -> impl ParallelIterator<Item = Vec<usize>> {
+    let total = if k > n || (k == 0 && n > 0) {
+        0
+    } else {
+        stirling2nd(n, k)
+    };
+    let table = rgs_table(n, k);
+    par_ranks(
+        total,
+        move |r| Rgs::new(unrank_rgs(r, n, &table)),
+        move |a| next_rgs(a, k),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combin::rank_comb;
+    use crate::perm::rank_perm;
+    use crate::SetPartition;
+
+    #[test]
+    fn test_par_combinations() {
+        for n in 0..9 {
+            for k in 0..=n {
+                let all: Vec<Vec<usize>> = par_combinations(n, k).collect();
+                assert_eq!(all.len(), comb(n, k));
+                for (r, c) in all.iter().enumerate() {
+                    assert_eq!(rank_comb(c, n, CombOrder::Lex), r);
+                }
+            }
+        }
+        assert_eq!(par_combinations(40, 5).count(), 658_008);
+    }
+
+    #[test]
+    fn test_par_permutations() {
+        for n in 0..7 {
+            let all: Vec<Vec<usize>> = par_permutations(n).collect();
+            assert_eq!(all.len(), factorial(n));
+            for (r, p) in all.iter().enumerate() {
+                assert_eq!(rank_perm(p, PermOrder::Lex), r);
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_set_partitions() {
+        assert_eq!(par_set_partitions(0, 0).count(), 1);
+        assert_eq!(par_set_partitions(3, 0).count(), 0);
+        assert_eq!(par_set_partitions(3, 4).count(), 0);
+        assert_eq!(
+            par_set_partitions(20, 20).collect::<Vec<_>>(),
+            [(0..20).collect::<Vec<_>>()]
+        );
+        assert_eq!(par_set_partitions(20, 19).count(), 190);
+        let table = rgs_table(25, 12);
+        assert_eq!(table[1][0], stirling2nd(25, 12));
+        let last = unrank_rgs(stirling2nd(25, 12) - 1, 25, &table);
+        assert_eq!(last[..12], (0..12).collect::<Vec<_>>());
+        assert!(last[12..].iter().all(|&b| b == 11));
+        for n in 1..9 {
+            for k in 1..=n {
+                let all: Vec<Vec<usize>> = par_set_partitions(n, k).collect();
+                assert_eq!(all.len(), stirling2nd(n, k));
+                assert!(all.windows(2).all(|w| w[0] < w[1]));
+                // same set of partitions as the Gray code, once relabelled
+                let mut gray = Vec::new();
+                let mut sp = SetPartition::new(n, k);
+                while let Some(p) = sp.advance() {
+                    let mut label = vec![usize::MAX; k];
+                    let mut next = 0;
+                    let rgs: Vec<usize> = p
+                        .rgs()
+                        .iter()
+                        .map(|&b| {
+                            if label[b] == usize::MAX {
+                                label[b] = next;
+                                next += 1;
+                            }
+                            label[b]
+                        })
+                        .collect();
+                    gray.push(rgs);
+                }
+                gray.sort();
+                assert_eq!(all, gray);
+            }
+        }
+    }
+}
@@ -3,12 +3,38 @@
 //! This module provides functionality for generating permutations using the
 //! Steinhaus-Johnson-Trotter (SJT) algorithm and Ehrlich algorithm.
 //!
+//! [`SjtIter`] and [`EhrIter`] are plain iterators yielding the same swaps as
+//! [`sjt_gen`] and [`ehr_gen`], without boxing a generator per recursion level.
+//! [`HeapIter`] (Heap's algorithm), [`EvenIter`] (Even's loopless variant of
+//! SJT) and [`LexIter`] (lexicographic next-permutation) are alternatives with
+//! the same interface.
+//!
+//! [`Permutations`] applies those swaps to an owned list of arbitrary items.
+//!
+//! [`rank_perm`] and [`unrank_perm`] convert between permutations and their
+//! position in lexicographic or SJT order, via the Lehmer code and factoradic
+//! digits.
+//!
+//! [`checked_factorial`] and [`checked_factorial_u128`] size an enumeration without
+//! overflowing; `factorial_big` (with the `bigint` feature) is exact for any `n`.
+//!
 //! ## Complexity
 //!
-//! - `factorial(n)`: O(n) time, O(n) stack space
+//! - `factorial(n)`: O(n) time, O(1) space
 //! - `sjt_gen(n)`: O(n!) output size, O(n) auxiliary space
 //! - `ehr_gen(n)`: O(n!) output size, O(n) auxiliary space
+//! - `SjtIter::new(n)`, `EhrIter::new(n)`: O(1) amortized time per swap, O(n) space
+//! - `HeapIter::new(n)`: O(1) amortized time per swap, O(n) space
+//! - `EvenIter::new(n)`: O(1) worst-case time per swap, O(n) space
+//! - `LexIter::new(n)`: O(1) amortized time per step, O(n) space
+//! - `Permutations`: the same, plus applying each step to the items
+//! - `rank_perm`, `unrank_perm`: O(n²) time, O(n) space
 
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::iter::FusedIterator;
+#[cfg(feature = "generator")]
 use genawaiter::sync::{Gen, GenBoxed};
 
 // /// For future rust version:
@@ -39,12 +65,95 @@ use genawaiter::sync::{Gen, GenBoxed};
 /// assert_eq!(factorial(5), 120);
 /// assert_eq!(factorial(1), 1);
 /// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; use [`checked_factorial`] to size an
+/// enumeration before starting it.
 pub const fn factorial(n: usize) -> usize {
-    if n < 2 {
-        1
-    } else {
-        n * factorial(n - 1)
+    match checked_factorial(n) {
+        Some(r) => r,
+        None => panic!("factorial: result overflows usize"),
+    }
+}
+
+/// The `checked_factorial` function calculates n!, returning `None` instead of overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number for which we want to calculate the factorial.
+///
+/// Returns:
+///
+/// `Some(n!)`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_factorial;
+///
+/// assert_eq!(checked_factorial(5), Some(120));
+/// assert_eq!(checked_factorial(usize::BITS as usize), None);
+/// ```
+pub const fn checked_factorial(n: usize) -> Option<usize> {
+    match checked_factorial_u128(n as u128) {
+        Some(r) if r <= usize::MAX as u128 => Some(r as usize),
+        _ => None,
+    }
+}
+
+/// The `checked_factorial_u128` function calculates n! in `u128`, returning `None` instead of
+/// overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number for which we want to calculate the factorial.
+///
+/// Returns:
+///
+/// `Some(n!)`, or `None` if it does not fit in a `u128` (that is, for `n > 34`).
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_factorial_u128;
+///
+/// assert_eq!(checked_factorial_u128(21), Some(51_090_942_171_709_440_000));
+/// assert_eq!(checked_factorial_u128(35), None);
+/// ```
+pub const fn checked_factorial_u128(n: u128) -> Option<u128> {
+    let mut r: u128 = 1;
+    let mut i: u128 = 2;
+    while i <= n {
+        r = match r.checked_mul(i) {
+            Some(v) => v,
+            None => return None,
+        };
+        i += 1;
     }
+    Some(r)
+}
+
+/// The `factorial_big` function calculates n! exactly.
+///
+/// Arguments:
+///
+/// * `n`: The number for which we want to calculate the factorial.
+///
+/// Returns:
+///
+/// n! as a [`BigUint`](num_bigint::BigUint).
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::factorial_big;
+///
+/// assert_eq!(factorial_big(35).to_string(), "10333147966386144929666651337523200000000");
+/// ```
+#[cfg(feature = "bigint")]
+pub fn factorial_big(n: usize) -> num_bigint::BigUint {
+    (2..=n).fold(num_bigint::BigUint::from(1u32), |acc, i| acc * i)
 }
 
 /// Generate all permutations by adjacent transposition
@@ -76,6 +185,7 @@ pub const fn factorial(n: usize) -> usize {
 /// assert_eq!(cnt, 24);
 /// assert_eq!(perm, ["🍉", "🍌", "🍇", "🍏"]); // Hamilton cycle
 /// ```
+#[cfg(feature = "generator")]
 pub fn sjt_gen(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| {
         async move {
@@ -139,6 +249,7 @@ pub fn sjt_gen(n: usize) -> GenBoxed<usize> {
 /// assert_eq!(cnt, 24);
 /// assert_eq!(perm, ["🍏", "🍌", "🍇", "🍉"]);
 /// ```
+#[cfg(feature = "generator")]
 pub fn ehr_gen(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| {
         async move {
@@ -166,10 +277,1228 @@ pub fn ehr_gen(n: usize) -> GenBoxed<usize> {
     })
 }
 
+/// State of one level of the SJT recursion: the sweep position within the
+/// current block, the sweep direction, and the swap fetched from the level below.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SjtLevel {
+    pos: usize,
+    even: bool,
+    pending: usize,
+}
+
+/// Iterator over the adjacent transpositions of the Steinhaus-Johnson-Trotter algorithm
+///
+/// `SjtIter` yields exactly the same swaps as [`sjt_gen`]: each item `i` means
+/// swapping the elements at positions `i` and `i + 1`. All `n!` swaps are
+/// yielded, the last of which returns to the original list.
+///
+/// The recursion of [`sjt_gen`] is kept as an explicit stack of levels: level
+/// `m` sweeps the largest of its `m` elements across the others, and only asks
+/// level `m - 1` for a swap at the start of each sweep.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::SjtIter;
+///
+/// let mut perm = ["🍉", "🍌", "🍇", "🍏"];
+/// let mut cnt = 0;
+/// for n in SjtIter::new(perm.len()) {
+///     perm.swap(n, n + 1);
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 24);
+/// assert_eq!(perm, ["🍉", "🍌", "🍇", "🍏"]); // Hamilton cycle
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SjtState")
+)]
+pub struct SjtIter {
+    levels: Vec<SjtLevel>,
+    base: usize,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct SjtState {
+    levels: Vec<SjtLevel>,
+    base: usize,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<SjtState> for SjtIter {
+    type Error = &'static str;
+
+    fn try_from(state: SjtState) -> Result<Self, Self::Error> {
+        let SjtState {
+            levels,
+            base,
+            remaining,
+            done,
+        } = state;
+        let n = levels.len().wrapping_sub(1);
+        // each sweep stays within its level, and so do the swaps it fetched
+        let valid = !levels.is_empty()
+            && base <= 2
+            && (done || n >= 2 && (3..=n).all(|m| levels[m].pos < m && levels[m].pending < m - 2));
+        if !valid {
+            return Err("invalid SjtIter state");
+        }
+        Ok(Self {
+            levels,
+            base,
+            remaining,
+            done,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SjtIter {
+    /// Creates the iterator for permutations of `n` elements.
+    ///
+    /// For `n < 2` there is nothing to swap and the iterator is empty.
+    pub fn new(n: usize) -> Self {
+        let levels = vec![
+            SjtLevel {
+                even: true,
+                ..SjtLevel::default()
+            };
+            n + 1
+        ];
+        Self {
+            levels,
+            base: 0,
+            remaining: if n < 2 { Some(0) } else { checked_factorial(n) },
+            done: n < 2,
+        }
+    }
+
+    /// Takes the next swap of level `m`, where level `m` either is the base
+    /// level 2 or is in the middle of a sweep.
+    fn step(&mut self, m: usize) -> Option<usize> {
+        if m == 2 {
+            if self.base == 2 {
+                return None;
+            }
+            self.base += 1;
+            return Some(0);
+        }
+        let level = &mut self.levels[m];
+        if level.pos < m - 1 {
+            let i = if level.even {
+                m - 2 - level.pos
+            } else {
+                level.pos
+            };
+            level.pos += 1;
+            Some(i)
+        } else {
+            let i = if level.even {
+                1 + level.pending
+            } else {
+                level.pending
+            };
+            level.even = !level.even;
+            level.pos = 0;
+            Some(i)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for SjtIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.done {
+            return None;
+        }
+        let n = self.levels.len() - 1;
+        // Levels at the start of a sweep need a swap from the level below.
+        let mut m = n;
+        while m > 2 && self.levels[m].pos == 0 {
+            m -= 1;
+        }
+        let Some(mut i) = self.step(m) else {
+            self.done = true;
+            return None;
+        };
+        for (l, level) in self.levels.iter_mut().enumerate().skip(m + 1) {
+            level.pending = i;
+            i = if level.even { l - 2 } else { 0 };
+            level.pos = 1;
+        }
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some(i)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for SjtIter {}
+
+/// Iterator over the star transpositions of Ehrlich's algorithm
+///
+/// `EhrIter` yields exactly the same swaps as [`ehr_gen`]: each item `i` means
+/// swapping the elements at positions `0` and `i`. There are `n! - 1` swaps.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::EhrIter;
+///
+/// let mut perm = ["🍉", "🍌", "🍇", "🍏"];
+/// let mut cnt = 1;
+/// for n in EhrIter::new(perm.len()) {
+///     perm.swap(0, n);
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 24);
+/// assert_eq!(perm, ["🍏", "🍌", "🍇", "🍉"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "EhrState")
+)]
+pub struct EhrIter {
+    c: Vec<usize>, // c[0] is never used
+    b: Vec<usize>,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct EhrState {
+    c: Vec<usize>,
+    b: Vec<usize>,
+    remaining: Option<usize>,
+    done: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<EhrState> for EhrIter {
+    type Error = &'static str;
+
+    fn try_from(state: EhrState) -> Result<Self, Self::Error> {
+        let EhrState {
+            c,
+            b,
+            remaining,
+            done,
+        } = state;
+        let n = b.len();
+        let valid = c.len() == n + 1
+            && c.iter().enumerate().all(|(k, &ck)| ck <= k)
+            && c[0] == 0
+            && c[n] == 0
+            && is_perm(&b)
+            && b.first().map_or(true, |&b0| b0 == 0)
+            && (done || n >= 2);
+        if !valid {
+            return Err("invalid EhrIter state");
+        }
+        Ok(Self {
+            c,
+            b,
+            remaining,
+            done,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EhrIter {
+    /// Creates the iterator for permutations of `n` elements.
+    ///
+    /// For `n < 2` there is nothing to swap and the iterator is empty.
+    pub fn new(n: usize) -> Self {
+        Self {
+            c: vec![0; n + 1],
+            b: (0..n).collect(),
+            remaining: if n < 2 {
+                Some(0)
+            } else {
+                checked_factorial(n).map(|r| r - 1)
+            },
+            done: n < 2,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for EhrIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.done {
+            return None;
+        }
+        let n = self.b.len();
+        let c = &mut self.c;
+        let mut k: usize = 1;
+        loop {
+            if c[k] == k {
+                c[k] = 0;
+                k += 1;
+            }
+            if c[k] < k {
+                break;
+            }
+        }
+        if k == n {
+            self.done = true;
+            return None;
+        }
+        c[k] += 1;
+        let i = self.b[k];
+        self.b[1..k].reverse();
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some(i)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for EhrIter {}
+
+/// Positions `(i, j)` of two items exchanged by a permutation generator.
+pub type Transposition = (usize, usize);
+
+/// Iterator over the swaps of Heap's algorithm
+///
+/// Each item `(i, j)` with `i < j` asks for `p.swap(i, j)`. Starting from any
+/// arrangement, the `factorial(n) - 1` swaps visit every permutation exactly
+/// once. Unlike SJT and Ehrlich's algorithm the swapped positions are not
+/// restricted, but each step does the least bookkeeping of the three.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::HeapIter;
+///
+/// let mut perm = ['a', 'b', 'c'];
+/// let mut seen = vec![perm.iter().collect::<String>()];
+/// for (i, j) in HeapIter::new(perm.len()) {
+///     perm.swap(i, j);
+///     seen.push(perm.iter().collect());
+/// }
+///
+/// assert_eq!(seen, ["abc", "bac", "cab", "acb", "bca", "cba"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "HeapState")
+)]
+pub struct HeapIter {
+    /// Loop counter of each level of the recursive formulation.
+    c: Vec<usize>,
+    i: usize,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct HeapState {
+    c: Vec<usize>,
+    i: usize,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<HeapState> for HeapIter {
+    type Error = &'static str;
+
+    fn try_from(state: HeapState) -> Result<Self, Self::Error> {
+        let HeapState { c, i, remaining } = state;
+        // between swaps the levels restart from 1, until all of them are done
+        let valid = c.iter().enumerate().all(|(k, &ck)| ck <= k)
+            && c.first().map_or(true, |&c0| c0 == 0)
+            && (i == 1 || i == c.len() && c.iter().all(|&ck| ck == 0));
+        if !valid {
+            return Err("invalid HeapIter state");
+        }
+        Ok(Self { c, i, remaining })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl HeapIter {
+    /// Creates the iterator for permutations of `n` elements.
+    ///
+    /// The iterator is empty for `n < 2`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            c: vec![0; n],
+            i: 1,
+            remaining: checked_factorial(n).map(|r| r - 1),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for HeapIter {
+    type Item = Transposition;
+
+    fn next(&mut self) -> Option<Transposition> {
+        while self.i < self.c.len() {
+            let i = self.i;
+            if self.c[i] < i {
+                let j = if i % 2 == 0 { 0 } else { self.c[i] };
+                self.c[i] += 1;
+                self.i = 1;
+                self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+                return Some((j, i));
+            }
+            self.c[i] = 0;
+            self.i += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for HeapIter {}
+
+/// Loopless iterator over the adjacent transpositions of the SJT order
+///
+/// `EvenIter` implements Even's speedup of the Steinhaus–Johnson–Trotter
+/// algorithm: every element carries a direction, and the element to move next
+/// is found in constant time through focus pointers, so each step takes O(1)
+/// time in the worst case rather than amortized. It yields the same swaps as
+/// [`SjtIter`] except for the final one that closes the Hamilton cycle, i.e.
+/// `factorial(n) - 1` indices `i`, each asking for `p.swap(i, i + 1)`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::{EvenIter, SjtIter};
+///
+/// let n = 5;
+/// assert!(EvenIter::new(n).eq(SjtIter::new(n).take(119)));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "EvenState")
+)]
+pub struct EvenIter {
+    /// Current arrangement and its inverse.
+    perm: Vec<usize>,
+    pos: Vec<usize>,
+    /// Reflected mixed-radix digits: `digit[j]` is the offset of element `n-1-j`
+    /// among the smaller elements, which moves left while `left[j]` holds.
+    digit: Vec<usize>,
+    left: Vec<bool>,
+    focus: Vec<usize>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct EvenState {
+    perm: Vec<usize>,
+    pos: Vec<usize>,
+    digit: Vec<usize>,
+    left: Vec<bool>,
+    focus: Vec<usize>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<EvenState> for EvenIter {
+    type Error = &'static str;
+
+    fn try_from(state: EvenState) -> Result<Self, Self::Error> {
+        let EvenState {
+            perm,
+            pos,
+            digit,
+            left,
+            focus,
+            remaining,
+        } = state;
+        let n = perm.len();
+        let digits = n.saturating_sub(1);
+        let mut valid = pos.len() == n
+            && perm
+                .iter()
+                .enumerate()
+                .all(|(p, &e)| pos.get(e) == Some(&p))
+            && digit.len() == digits
+            && left.len() == digits
+            && focus.len() == digits + 1
+            && focus[digits] == digits
+            && digit.iter().enumerate().all(|(j, &d)| d < n - 1 - j);
+        if valid {
+            // the arrangement follows from where each element is in its sweep
+            // across the smaller ones
+            let mut arrangement = Vec::with_capacity(n);
+            arrangement.extend(perm.first().map(|_| 0));
+            for e in 1..n {
+                let j = n - 1 - e;
+                let slot = if left[j] { e - digit[j] } else { digit[j] };
+                arrangement.insert(slot, e);
+            }
+            valid = arrangement == perm;
+        }
+        // a focus pointer skips a run of digits that have just finished, up
+        // to the next digit that can move
+        let mut j = 0;
+        while valid && j < digits {
+            let f = focus[j];
+            valid = j <= f
+                && f <= digits
+                && digit[j..f].iter().all(|&d| d == 0)
+                && (j + 1..f).all(|x| focus[x] == x)
+                && (f == j || focus[f] == f);
+            j = if f == j { j + 1 } else { f };
+        }
+        if !valid {
+            return Err("invalid EvenIter state");
+        }
+        Ok(Self {
+            perm,
+            pos,
+            digit,
+            left,
+            focus,
+            remaining,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EvenIter {
+    /// Creates the iterator for permutations of `n` elements.
+    ///
+    /// The iterator is empty for `n < 2`.
+    pub fn new(n: usize) -> Self {
+        let digits = n.saturating_sub(1);
+        Self {
+            perm: (0..n).collect(),
+            pos: (0..n).collect(),
+            digit: vec![0; digits],
+            left: vec![true; digits],
+            focus: (0..=digits).collect(),
+            remaining: checked_factorial(n).map(|r| r - 1),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for EvenIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let j = self.focus[0];
+        if j == self.digit.len() {
+            return None;
+        }
+        self.focus[0] = 0;
+        let n = self.perm.len();
+        let elem = n - 1 - j;
+        let p = self.pos[elem];
+        let (i, q) = if self.left[j] {
+            (p - 1, p - 1)
+        } else {
+            (p, p + 1)
+        };
+        let other = self.perm[q];
+        self.perm.swap(p, q);
+        self.pos[elem] = q;
+        self.pos[other] = p;
+        self.digit[j] += 1;
+        // element n-1-j has n-j slots among the smaller elements
+        if self.digit[j] == n - 1 - j {
+            self.digit[j] = 0;
+            self.left[j] = !self.left[j];
+            self.focus[j] = self.focus[j + 1];
+            self.focus[j + 1] = j + 1;
+        }
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some(i)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for EvenIter {}
+
+/// Iterator over the steps of lexicographic next-permutation
+///
+/// Each item `(i, j)` asks for `p.swap(i, j)` followed by `p[i + 1..].reverse()`,
+/// which turns the current arrangement into its lexicographic successor
+/// (comparing the original positions of the items, so the items themselves
+/// need not be ordered or distinct). Starting from the identity, the
+/// `factorial(n) - 1` steps visit all permutations in lexicographic order.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::LexIter;
+///
+/// let mut perm = [0, 1, 2];
+/// let mut seen = vec![perm];
+/// for (i, j) in LexIter::new(perm.len()) {
+///     perm.swap(i, j);
+///     perm[i + 1..].reverse();
+///     seen.push(perm);
+/// }
+///
+/// assert_eq!(seen, [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "LexState")
+)]
+pub struct LexIter {
+    perm: Vec<usize>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct LexState {
+    perm: Vec<usize>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<LexState> for LexIter {
+    type Error = &'static str;
+
+    fn try_from(state: LexState) -> Result<Self, Self::Error> {
+        let LexState { perm, remaining } = state;
+        if !is_perm(&perm) {
+            return Err("invalid LexIter state");
+        }
+        Ok(Self { perm, remaining })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl LexIter {
+    /// Creates the iterator for permutations of `n` elements.
+    ///
+    /// The iterator is empty for `n < 2`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            perm: (0..n).collect(),
+            remaining: checked_factorial(n).map(|r| r - 1),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for LexIter {
+    type Item = Transposition;
+
+    fn next(&mut self) -> Option<Transposition> {
+        let p = &mut self.perm;
+        // rightmost ascent p[i] < p[i + 1]
+        let i = (0..p.len().saturating_sub(1))
+            .rev()
+            .find(|&i| p[i] < p[i + 1])?;
+        // rightmost element larger than p[i]
+        let j = (i + 1..p.len()).rev().find(|&j| p[j] > p[i])?;
+        p.swap(i, j);
+        p[i + 1..].reverse();
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some((i, j))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for LexIter {}
+
+/// Source of the swaps applied by [`Permutations`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum PermSwaps {
+    /// The SJT swaps, one ahead, so that the closing swap can be recognised.
+    Sjt {
+        iter: SjtIter,
+        next: Option<usize>,
+    },
+    Ehr(EhrIter),
+    Heap(HeapIter),
+    Even(EvenIter),
+    Lex(LexIter),
+}
+
+#[cfg(feature = "alloc")]
+impl PermSwaps {
+    /// The next pair of positions to swap, leaving out the final swap of SJT,
+    /// which only closes the Hamilton cycle.
+    fn next(&mut self) -> Option<Transposition> {
+        match self {
+            PermSwaps::Sjt { iter, next } => {
+                let i = (*next)?;
+                *next = iter.next();
+                // the last swap is the one with nothing after it
+                next.map(|_| (i, i + 1))
+            }
+            PermSwaps::Ehr(iter) => iter.next().map(|i| (0, i)),
+            PermSwaps::Heap(iter) => iter.next(),
+            PermSwaps::Even(iter) => iter.next().map(|i| (i, i + 1)),
+            PermSwaps::Lex(iter) => iter.next(),
+        }
+    }
+}
+
+/// Stateful permutation of arbitrary items
+///
+/// `Permutations` owns a list of items and rearranges it in place by applying
+/// the swaps of [`SjtIter`] (adjacent transpositions, `p.swap(i, i + 1)`),
+/// [`EhrIter`] (star transpositions, `p.swap(0, i)`), [`HeapIter`],
+/// [`EvenIter`] or [`LexIter`]. Every arrangement of the items is visited
+/// exactly once, starting with the items in the order given.
+///
+/// Since the views borrow the state, `Permutations` is advanced with
+/// [`Permutations::advance`] rather than through the `Iterator` trait.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::Permutations;
+///
+/// let mut perms = Permutations::sjt(vec!["a", "b", "c"]);
+/// let mut seen = Vec::new();
+/// while let Some(p) = perms.advance() {
+///     seen.push(p.concat());
+/// }
+///
+/// assert_eq!(seen, ["abc", "acb", "cab", "cba", "bca", "bac"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "PermutationsState<T>")
+)]
+pub struct Permutations<T> {
+    items: Vec<T>,
+    swaps: PermSwaps,
+    last_swap: Option<Transposition>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct PermutationsState<T> {
+    items: Vec<T>,
+    swaps: PermSwaps,
+    last_swap: Option<Transposition>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl<T> TryFrom<PermutationsState<T>> for Permutations<T> {
+    type Error = &'static str;
+
+    fn try_from(state: PermutationsState<T>) -> Result<Self, Self::Error> {
+        let PermutationsState {
+            items,
+            swaps,
+            last_swap,
+            started,
+        } = state;
+        let n = items.len();
+        // the swaps must be for as many items as there are
+        let valid = match &swaps {
+            PermSwaps::Sjt { iter, next } => {
+                iter.levels.len() == n + 1 && next.map_or(true, |i| i + 1 < n)
+            }
+            PermSwaps::Ehr(iter) => iter.b.len() == n,
+            PermSwaps::Heap(iter) => iter.c.len() == n,
+            PermSwaps::Even(iter) => iter.perm.len() == n,
+            PermSwaps::Lex(iter) => iter.perm.len() == n,
+        } && last_swap.map_or(true, |(i, j)| i < n && j < n);
+        if !valid {
+            return Err("invalid Permutations state");
+        }
+        Ok(Self {
+            items,
+            swaps,
+            last_swap,
+            started,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Permutations<T> {
+    /// Creates the permutations of `items` in Steinhaus–Johnson–Trotter order,
+    /// where successive arrangements differ by swapping two adjacent items.
+    pub fn sjt(items: impl Into<Vec<T>>) -> Self {
+        let items = items.into();
+        let mut iter = SjtIter::new(items.len());
+        let next = iter.next();
+        let swaps = PermSwaps::Sjt { iter, next };
+        Self::with_swaps(items, swaps)
+    }
+
+    /// Creates the permutations of `items` in Ehrlich's star transposition
+    /// order, where successive arrangements differ by swapping the first item
+    /// with another one.
+    pub fn ehr(items: impl Into<Vec<T>>) -> Self {
+        let items = items.into();
+        let swaps = PermSwaps::Ehr(EhrIter::new(items.len()));
+        Self::with_swaps(items, swaps)
+    }
+
+    /// Creates the permutations of `items` in the order of Heap's algorithm,
+    /// where successive arrangements differ by one (not necessarily adjacent) swap.
+    pub fn heap(items: impl Into<Vec<T>>) -> Self {
+        let items = items.into();
+        let swaps = PermSwaps::Heap(HeapIter::new(items.len()));
+        Self::with_swaps(items, swaps)
+    }
+
+    /// Creates the permutations of `items` in SJT order using Even's loopless
+    /// algorithm; the arrangements are the same as for [`Permutations::sjt`].
+    pub fn even(items: impl Into<Vec<T>>) -> Self {
+        let items = items.into();
+        let swaps = PermSwaps::Even(EvenIter::new(items.len()));
+        Self::with_swaps(items, swaps)
+    }
+
+    /// Creates the permutations of `items` in lexicographic order of their
+    /// original positions.
+    ///
+    /// Each step swaps the reported positions `(i, j)` and then reverses the
+    /// items after position `i`, so successive arrangements may differ in more
+    /// than two places.
+    pub fn lex(items: impl Into<Vec<T>>) -> Self {
+        let items = items.into();
+        let swaps = PermSwaps::Lex(LexIter::new(items.len()));
+        Self::with_swaps(items, swaps)
+    }
+
+    fn with_swaps(items: Vec<T>, swaps: PermSwaps) -> Self {
+        Self {
+            items,
+            swaps,
+            last_swap: None,
+            started: false,
+        }
+    }
+
+    /// Moves to the next arrangement and returns a view of it.
+    ///
+    /// The first call returns the items in their original order; `None` is
+    /// returned once all `factorial(n)` arrangements have been visited.
+    pub fn advance(&mut self) -> Option<&[T]> {
+        self.advance_with_swap().map(|(items, _)| items)
+    }
+
+    /// Like [`Permutations::advance`], but also returns the positions `(i, j)`
+    /// that were swapped to reach the new arrangement (`None` for the first one).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::perm::Permutations;
+    ///
+    /// let mut perms = Permutations::ehr([1, 2, 3]);
+    /// assert_eq!(perms.advance_with_swap(), Some((&[1, 2, 3][..], None)));
+    /// assert_eq!(perms.advance_with_swap(), Some((&[2, 1, 3][..], Some((0, 1)))));
+    /// ```
+    pub fn advance_with_swap(&mut self) -> Option<(&[T], Option<Transposition>)> {
+        if !self.started {
+            self.started = true;
+            return Some((&self.items, None));
+        }
+        let (i, j) = self.swaps.next()?;
+        self.items.swap(i, j);
+        if let PermSwaps::Lex(_) = self.swaps {
+            self.items[i + 1..].reverse();
+        }
+        self.last_swap = Some((i, j));
+        Some((&self.items, self.last_swap))
+    }
+
+    /// The current arrangement.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The swap that produced the current arrangement, or `None` for the
+    /// original order.
+    #[inline]
+    pub fn last_swap(&self) -> Option<Transposition> {
+        self.last_swap
+    }
+
+    /// Returns the items in their current arrangement.
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+}
+
+/// Whether `p` is an arrangement of `0..p.len()`.
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub(crate) fn is_perm(p: &[usize]) -> bool {
+    let mut seen = vec![false; p.len()];
+    p.iter()
+        .all(|&x| x < p.len() && !core::mem::replace(&mut seen[x], true))
+}
+
+/// Orders for ranking and unranking permutations
+///
+/// Permutations are given as arrangements of `{0, 1, ..., n-1}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PermOrder {
+    /// Lexicographic order, e.g. `012, 021, 102, 120, 201, 210`.
+    Lex,
+    /// The Steinhaus–Johnson–Trotter order obtained by applying the swaps of
+    /// [`sjt_gen`] to the identity, e.g. `012, 021, 201, 210, 120, 102`.
+    Sjt,
+}
+
+/// Lehmer code of a permutation
+///
+/// Entry `i` of the Lehmer code counts the entries after position `i` that are
+/// smaller than `perm[i]`, so it is less than `n - i`. Read as factoradic digits
+/// (most significant first), the Lehmer code is the lexicographic rank.
+///
+/// Arguments:
+///
+/// * `perm`: A permutation of `{0, 1, ..., n-1}`.
+///
+/// Returns:
+///
+/// The function `lehmer_code` returns the `n` digits of the Lehmer code.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::lehmer_code;
+///
+/// assert_eq!(lehmer_code(&[2, 0, 3, 1]), [2, 0, 1, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn lehmer_code(perm: &[usize]) -> Vec<usize> {
+    perm.iter()
+        .enumerate()
+        .map(|(i, &x)| perm[i + 1..].iter().filter(|&&y| y < x).count())
+        .collect()
+}
+
+/// Permutation with a given Lehmer code
+///
+/// The `from_lehmer_code` function is the inverse of [`lehmer_code`].
+///
+/// Arguments:
+///
+/// * `code`: A Lehmer code, with `code[i] < n - i`.
+///
+/// Returns:
+///
+/// The function `from_lehmer_code` returns the permutation of `{0, 1, ..., n-1}`.
+///
+/// # Panics
+///
+/// Panics if some `code[i]` is not less than `n - i`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::from_lehmer_code;
+///
+/// assert_eq!(from_lehmer_code(&[2, 0, 1, 0]), [2, 0, 3, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn from_lehmer_code(code: &[usize]) -> Vec<usize> {
+    let mut unused: Vec<usize> = (0..code.len()).collect();
+    code.iter().map(|&d| unused.remove(d)).collect()
+}
+
+/// Factoradic digits of a number
+///
+/// The `to_factoradic` function writes `r` in the factorial number system with
+/// `n` digits, most significant first: `r = sum(digits[i] * (n-1-i)!)` with
+/// `digits[i] < n - i`.
+///
+/// Arguments:
+///
+/// * `r`: The number to convert, less than `factorial(n)`.
+/// * `n`: The number of digits.
+///
+/// Returns:
+///
+/// The function `to_factoradic` returns the `n` digits.
+///
+/// # Panics
+///
+/// Panics if `r` does not fit in `n` factoradic digits.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::to_factoradic;
+///
+/// assert_eq!(to_factoradic(463, 6), [3, 4, 1, 0, 1, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_factoradic(mut r: usize, n: usize) -> Vec<usize> {
+    let mut digits = vec![0; n];
+    for (i, d) in digits.iter_mut().rev().enumerate() {
+        *d = r % (i + 1);
+        r /= i + 1;
+    }
+    assert_eq!(r, 0, "rank out of range");
+    digits
+}
+
+/// Number with given factoradic digits
+///
+/// The `from_factoradic` function is the inverse of [`to_factoradic`].
+///
+/// Arguments:
+///
+/// * `digits`: The factoradic digits, most significant first, with
+///   `digits[i] < digits.len() - i`.
+///
+/// Returns:
+///
+/// The function `from_factoradic` returns the value of the digits.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::from_factoradic;
+///
+/// assert_eq!(from_factoradic(&[3, 4, 1, 0, 1, 0]), 463);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn from_factoradic(digits: &[usize]) -> usize {
+    let n = digits.len();
+    digits
+        .iter()
+        .enumerate()
+        .fold(0, |r, (i, &d)| r * (n - i) + d)
+}
+
+/// Rank of a permutation in lex or SJT order
+///
+/// The `rank_perm` function returns the position (starting from 0) of `perm` in
+/// the list of all permutations of `{0, 1, ..., n-1}` in the given order. The
+/// ranks range over `0..factorial(n)`, so a search space can be split into
+/// ranges and resumed with [`unrank_perm`].
+///
+/// Arguments:
+///
+/// * `perm`: A permutation of `{0, 1, ..., n-1}`.
+/// * `order`: The order in which the permutations are listed.
+///
+/// Returns:
+///
+/// The function `rank_perm` returns the rank of `perm` as a `usize`.
+///
+/// # Panics
+///
+/// Panics if `factorial(n)` does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::{rank_perm, PermOrder};
+///
+/// assert_eq!(rank_perm(&[1, 2, 0], PermOrder::Lex), 3);
+/// assert_eq!(rank_perm(&[1, 2, 0], PermOrder::Sjt), 4);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn rank_perm(perm: &[usize], order: PermOrder) -> usize {
+    let n = perm.len();
+    assert!(checked_factorial(n).is_some(), "rank overflows usize");
+    debug_assert!({
+        let mut sorted = perm.to_vec();
+        sorted.sort_unstable();
+        sorted.iter().copied().eq(0..n)
+    });
+    match order {
+        PermOrder::Lex => from_factoradic(&lehmer_code(perm)),
+        PermOrder::Sjt => {
+            // Element j sweeps through the j + 1 slots among the smaller
+            // elements, right to left when the rank of the smaller elements
+            // is even and left to right when it is odd.
+            let mut r = 0;
+            for j in 1..n {
+                let k = perm.iter().filter(|&&x| x <= j).position(|&x| x == j);
+                let k = k.unwrap_or(0);
+                r = if r % 2 == 0 {
+                    (j + 1) * r + j - k
+                } else {
+                    (j + 1) * r + k
+                };
+            }
+            r
+        }
+    }
+}
+
+/// Permutation of a given rank in lex or SJT order
+///
+/// The `unrank_perm` function is the inverse of [`rank_perm`].
+///
+/// Arguments:
+///
+/// * `r`: The rank of the permutation, less than `factorial(n)`.
+/// * `n`: The number of elements.
+/// * `order`: The order in which the permutations are listed.
+///
+/// Returns:
+///
+/// The function `unrank_perm` returns the permutation of `{0, 1, ..., n-1}`.
+///
+/// # Panics
+///
+/// Panics if `r` is not a valid rank.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::perm::{unrank_perm, PermOrder};
+///
+/// assert_eq!(unrank_perm(3, 3, PermOrder::Lex), [1, 2, 0]);
+/// assert_eq!(unrank_perm(3, 3, PermOrder::Sjt), [2, 1, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn unrank_perm(r: usize, n: usize, order: PermOrder) -> Vec<usize> {
+    assert!(
+        checked_factorial(n).map_or(true, |f| r < f),
+        "rank out of range"
+    );
+    match order {
+        PermOrder::Lex => from_lehmer_code(&to_factoradic(r, n)),
+        PermOrder::Sjt => {
+            // prefix[j] is the rank of the arrangement of {0, ..., j}
+            let mut prefix = vec![0; n.max(1)];
+            let mut slot = vec![0; n.max(1)];
+            let mut q = r;
+            for j in (1..n).rev() {
+                slot[j] = q % (j + 1);
+                q /= j + 1;
+                prefix[j - 1] = q;
+            }
+            let mut perm = Vec::with_capacity(n);
+            if n > 0 {
+                perm.push(0);
+            }
+            for j in 1..n {
+                let k = if prefix[j - 1] % 2 == 0 {
+                    j - slot[j]
+                } else {
+                    slot[j]
+                };
+                perm.insert(k, j);
+            }
+            perm
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_factorial() {
+        assert_eq!(factorial(0), 1);
+        assert_eq!(factorial(1), 1);
+        assert_eq!(factorial(4), 24);
+        assert_eq!(factorial(20), 2_432_902_008_176_640_000);
+    }
+
+    #[test]
+    fn test_checked_factorial() {
+        let mut f: u128 = 1;
+        for n in 0..=34 {
+            f *= n.max(1);
+            assert_eq!(checked_factorial_u128(n), Some(f));
+        }
+        assert_eq!(checked_factorial_u128(35), None);
+        assert_eq!(checked_factorial(12), Some(479_001_600));
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(checked_factorial(20), Some(factorial(20)));
+            assert_eq!(checked_factorial(21), None);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_factorial_big() {
+        for n in 0..=34 {
+            let f = checked_factorial_u128(n as u128).unwrap();
+            assert_eq!(factorial_big(n), num_bigint::BigUint::from(f));
+        }
+        assert_eq!(factorial_big(40), factorial_big(39) * 40u32);
+    }
+
+    #[cfg(feature = "generator")]
     #[test]
     fn test_sjt() {
         let mut cnt = 0;
@@ -179,6 +1508,7 @@ mod tests {
         assert_eq!(cnt, factorial(4));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_ehr() {
         let mut cnt = 1;
@@ -187,4 +1517,205 @@ mod tests {
         }
         assert_eq!(cnt, factorial(4));
     }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_iters_match_generators() {
+        for n in 2..8 {
+            assert!(SjtIter::new(n).eq(sjt_gen(n)));
+            assert!(EhrIter::new(n).eq(ehr_gen(n)));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_iters_size_hint() {
+        let mut iter = SjtIter::new(5);
+        assert_eq!(iter.size_hint(), (120, Some(120)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (119, Some(119)));
+        assert_eq!(iter.count(), 119);
+        assert_eq!(EhrIter::new(5).size_hint(), (119, Some(119)));
+        assert_eq!(EhrIter::new(5).count(), 119);
+        assert_eq!(SjtIter::new(1).count(), 0);
+        assert_eq!(EhrIter::new(0).count(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_permutations_visit_all() {
+        use std::collections::HashSet;
+
+        for n in 0..8 {
+            let items: Vec<char> = ('a'..).take(n).collect();
+            for mut perms in [
+                Permutations::sjt(items.clone()),
+                Permutations::ehr(items.clone()),
+            ] {
+                let mut seen = HashSet::new();
+                let mut prev = items.clone();
+                while let Some((p, swap)) = perms.advance_with_swap() {
+                    if let Some((i, j)) = swap {
+                        prev.swap(i, j);
+                    }
+                    assert_eq!(p, &prev[..]);
+                    assert!(seen.insert(p.to_vec()));
+                }
+                assert_eq!(seen.len(), factorial(n));
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_permutations_swap_kinds() {
+        let mut perms = Permutations::sjt(vec![0; 5]);
+        while perms.advance().is_some() {
+            if let Some((i, j)) = perms.last_swap() {
+                assert_eq!(i + 1, j);
+            }
+        }
+        let mut perms = Permutations::ehr(vec![0; 5]);
+        while perms.advance().is_some() {
+            if let Some((i, _)) = perms.last_swap() {
+                assert_eq!(i, 0);
+            }
+        }
+        assert_eq!(Permutations::sjt(["x"]).into_inner(), ["x"]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_permutations_sjt_without_count() {
+        // as for n > 20, where n! overflows and the swaps have no size hint
+        let mut perms = Permutations::sjt(vec![0, 1, 2, 3]);
+        if let PermSwaps::Sjt { iter, .. } = &mut perms.swaps {
+            iter.remaining = None;
+        }
+        let mut seen = std::collections::HashSet::new();
+        while let Some(p) = perms.advance() {
+            assert!(seen.insert(p.to_vec()));
+        }
+        assert_eq!(seen.len(), 24);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_alternative_iters() {
+        use std::collections::HashSet;
+
+        for n in 0..8 {
+            let total = factorial(n);
+            let mut heap: Vec<usize> = (0..n).collect();
+            let mut lex = heap.clone();
+            let mut seen_heap = HashSet::from([heap.clone()]);
+            let mut all_lex = vec![lex.clone()];
+            for (i, j) in HeapIter::new(n) {
+                assert!(i < j);
+                heap.swap(i, j);
+                assert!(seen_heap.insert(heap.clone()));
+            }
+            for (i, j) in LexIter::new(n) {
+                lex.swap(i, j);
+                lex[i + 1..].reverse();
+                all_lex.push(lex.clone());
+            }
+            assert_eq!(seen_heap.len(), total);
+            assert_eq!(all_lex.len(), total);
+            assert!(all_lex.windows(2).all(|w| w[0] < w[1]));
+            // Even's algorithm is SJT without the swap closing the cycle
+            assert!(EvenIter::new(n).eq(SjtIter::new(n).take(total - 1)));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_alternative_iters_size_hint() {
+        for iter_len in [
+            HeapIter::new(7).size_hint(),
+            EvenIter::new(7).size_hint(),
+            LexIter::new(7).size_hint(),
+        ] {
+            assert_eq!(iter_len, (5039, Some(5039)));
+        }
+        assert_eq!(HeapIter::new(1).count(), 0);
+        assert_eq!(EvenIter::new(1).count(), 0);
+        assert_eq!(LexIter::new(0).count(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_permutations_alternative_orders() {
+        use std::collections::HashSet;
+
+        let items = ['w', 'x', 'y', 'z'];
+        for mut perms in [
+            Permutations::heap(items),
+            Permutations::even(items),
+            Permutations::lex(items),
+        ] {
+            let mut seen = HashSet::new();
+            while let Some(p) = perms.advance() {
+                assert!(seen.insert(p.to_vec()));
+            }
+            assert_eq!(seen.len(), 24);
+        }
+        let mut sjt = Permutations::sjt(items);
+        let mut even = Permutations::even(items);
+        while let Some(p) = sjt.advance() {
+            assert_eq!(Some(p), even.advance());
+        }
+        assert_eq!(even.advance(), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_lehmer_factoradic_roundtrip() {
+        for n in 0..7 {
+            for r in 0..factorial(n) {
+                let digits = to_factoradic(r, n);
+                assert_eq!(from_factoradic(&digits), r);
+                let perm = from_lehmer_code(&digits);
+                assert_eq!(lehmer_code(&perm), digits);
+            }
+        }
+        assert_eq!(to_factoradic(factorial(20) - 1, 20)[0], 19);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rank_perm_matches_iterators() {
+        for n in 0..8 {
+            let mut lex: Vec<usize> = (0..n).collect();
+            let mut sjt = lex.clone();
+            let mut lex_swaps = LexIter::new(n);
+            let mut sjt_swaps = SjtIter::new(n);
+            for r in 0..factorial(n) {
+                assert_eq!(rank_perm(&lex, PermOrder::Lex), r);
+                assert_eq!(unrank_perm(r, n, PermOrder::Lex), lex);
+                assert_eq!(rank_perm(&sjt, PermOrder::Sjt), r);
+                assert_eq!(unrank_perm(r, n, PermOrder::Sjt), sjt);
+                if let Some((i, j)) = lex_swaps.next() {
+                    lex.swap(i, j);
+                    lex[i + 1..].reverse();
+                }
+                if let Some(i) = sjt_swaps.next() {
+                    sjt.swap(i, i + 1);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rank_perm_large() {
+        let n = 20;
+        let last = factorial(n) - 1;
+        let perm = unrank_perm(last, n, PermOrder::Lex);
+        assert_eq!(perm, (0..n).rev().collect::<Vec<_>>());
+        for r in [0, 1, 12_345_678_901, last] {
+            let p = unrank_perm(r, n, PermOrder::Sjt);
+            assert_eq!(rank_perm(&p, PermOrder::Sjt), r);
+        }
+    }
 }
@@ -0,0 +1,828 @@
+//! Restricted permutation generation
+//!
+//! This module generates permutations of `{0, 1, ..., n-1}` that satisfy a
+//! constraint, each visited exactly once:
+//!
+//! - [`Derangements`] - permutations without fixed points, or without fixed
+//!   points at specified positions
+//! - [`CyclePermutations`] - involutions, and permutations with a given cycle type
+//!
+//! A permutation is given as the list of images, i.e. `p[i]` is the image of `i`.
+//! Like [`Permutations::advance_with_swap`](crate::perm::Permutations::advance_with_swap), both generators can report the
+//! swaps `p.swap(i, j)` that lead from one permutation to the next.
+//! The counts are [`derangement`](crate::counting::derangement),
+//! [`involution`](crate::counting::involution) and [`cycle_type_count`].
+//!
+//! ## Algorithm
+//!
+//! [`Derangements`] applies the adjacent transpositions of [`SjtIter`](crate::perm::SjtIter)
+//! and keeps track of the number of forbidden fixed points, which each swap
+//! changes by at most two. The fraction of permutations without fixed points
+//! tends to 1/e, so for large `n` about e swaps are needed per derangement on
+//! average (fewer when only some positions are forbidden).
+//!
+//! [`CyclePermutations`] writes a permutation as a sequence of cycles, each
+//! starting with its smallest element, and backtracks over the choice of the
+//! cycle lengths and of the remaining elements of each cycle.
+//!
+//! ## Complexity
+//!
+//! - `Derangements`: O(1) amortized time per permutation, O(n) space
+//! - `CyclePermutations`: O(n^2) worst-case time per permutation, O(n) space
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::combin::checked_comb;
+use crate::perm::checked_factorial;
+#[cfg(all(feature = "alloc", feature = "serde"))]
+use crate::perm::is_perm;
+#[cfg(feature = "alloc")]
+use crate::perm::{Permutations, Transposition};
+
+/// The `cycle_type_count` function calculates the number of permutations with a given cycle type.
+///
+/// Arguments:
+///
+/// * `lengths`: The lengths of the cycles, in any order; they sum to the number of elements.
+///
+/// Returns:
+///
+/// The number n! / (prod L^(m_L) m_L!) of permutations whose cycles have exactly the given
+/// lengths, where m_L is the number of cycles of length L.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::cycle_type_count;
+///
+/// assert_eq!(cycle_type_count(&[3, 1]), 8);
+/// assert_eq!(cycle_type_count(&[2, 2, 1]), 15);
+/// ```
+///
+/// # Panics
+///
+/// Panics if a length is zero or if the result does not fit in a `usize`; see
+/// [`checked_cycle_type_count`].
+pub const fn cycle_type_count(lengths: &[usize]) -> usize {
+    match checked_cycle_type_count(lengths) {
+        Some(r) => r,
+        None => panic!("cycle_type_count: result overflows usize"),
+    }
+}
+
+/// The `checked_cycle_type_count` function calculates the number of permutations with a given
+/// cycle type, returning `None` instead of overflowing.
+///
+/// For each distinct length L, the elements of the cycles of length L are chosen first and
+/// then grouped into cycles one at a time; every factor is at least one, so `None` is returned
+/// only when the result itself overflows.
+///
+/// Arguments:
+///
+/// * `lengths`: The lengths of the cycles, in any order.
+///
+/// Returns:
+///
+/// `Some(count)`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_cycle_type_count;
+///
+/// assert_eq!(checked_cycle_type_count(&[]), Some(1));
+/// assert_eq!(checked_cycle_type_count(&[5]), Some(24));
+/// assert_eq!(checked_cycle_type_count(&[40]), None);
+/// ```
+///
+/// # Panics
+///
+/// Panics if a length is zero.
+pub const fn checked_cycle_type_count(lengths: &[usize]) -> Option<usize> {
+    let mut rest: usize = 0;
+    let mut i = 0;
+    while i < lengths.len() {
+        assert!(lengths[i] > 0, "cycle lengths must be positive");
+        // more than usize::MAX elements cannot be counted either
+        rest = match rest.checked_add(lengths[i]) {
+            Some(v) => v,
+            None => return None,
+        };
+        i += 1;
+    }
+    let mut r: usize = 1;
+    i = 0;
+    while i < lengths.len() {
+        let len = lengths[i];
+        // only handle each distinct length at its first occurrence
+        let mut seen = false;
+        let mut m: usize = 0;
+        let mut j = 0;
+        while j < lengths.len() {
+            if lengths[j] == len {
+                seen |= j < i;
+                m += 1;
+            }
+            j += 1;
+        }
+        if !seen {
+            let total = match m.checked_mul(len) {
+                Some(v) => v,
+                None => return None,
+            };
+            r = match checked_mul(r, checked_comb(rest, total)) {
+                Some(v) => v,
+                None => return None,
+            };
+            let cycle = match checked_factorial(len - 1) {
+                Some(v) => v,
+                None => return None,
+            };
+            let mut c = 1;
+            while c <= m {
+                // c * len <= m * len, which did not overflow
+                r = match checked_mul(r, checked_comb(c * len - 1, len - 1)) {
+                    Some(v) => match v.checked_mul(cycle) {
+                        Some(v) => v,
+                        None => return None,
+                    },
+                    None => return None,
+                };
+                c += 1;
+            }
+            rest -= total;
+        }
+        i += 1;
+    }
+    Some(r)
+}
+
+const fn checked_mul(r: usize, c: Option<usize>) -> Option<usize> {
+    match c {
+        Some(c) => r.checked_mul(c),
+        None => None,
+    }
+}
+
+/// Stateful generator of derangements and partial derangements
+///
+/// `Derangements` visits every permutation `p` of `{0, 1, ..., n-1}` with
+/// `p[i] != i` for all forbidden positions `i` exactly once, in the order in
+/// which they appear in [`Permutations::sjt`] starting from the identity.
+///
+/// Since the views borrow the state, `Derangements` is advanced with
+/// [`Derangements::advance`] rather than through the `Iterator` trait.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::derangement;
+/// use ecgen::restricted::Derangements;
+///
+/// let mut ders = Derangements::new(4);
+/// let mut cnt = 0;
+/// while let Some(p) = ders.advance() {
+///     assert!(p.iter().enumerate().all(|(i, &x)| x != i));
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, derangement(4));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "DerangementsState")
+)]
+pub struct Derangements {
+    perms: Permutations<usize>,
+    forbidden: Vec<bool>,
+    fixed: usize,
+    /// The swaps made by the last call to `advance`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    swaps: Vec<Transposition>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct DerangementsState {
+    perms: Permutations<usize>,
+    forbidden: Vec<bool>,
+    fixed: usize,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<DerangementsState> for Derangements {
+    type Error = &'static str;
+
+    fn try_from(state: DerangementsState) -> Result<Self, Self::Error> {
+        let DerangementsState {
+            perms,
+            forbidden,
+            fixed,
+        } = state;
+        let p = perms.as_slice();
+        // `fixed` counts the forbidden positions that are fixed points
+        let valid = forbidden.len() == p.len()
+            && is_perm(p)
+            && fixed == (0..p.len()).filter(|&i| forbidden[i] && p[i] == i).count();
+        if !valid {
+            return Err("invalid Derangements state");
+        }
+        Ok(Self {
+            perms,
+            forbidden,
+            fixed,
+            swaps: Vec::new(),
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Derangements {
+    /// Creates the derangements of `n` elements, i.e. the permutations
+    /// without any fixed point.
+    pub fn new(n: usize) -> Self {
+        Self::with_forbidden(vec![true; n])
+    }
+
+    /// Creates the permutations of `n` elements that have no fixed point at
+    /// any of the given `positions`; other positions may be fixed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a position is not less than `n`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::restricted::Derangements;
+    ///
+    /// let mut perms = Derangements::avoiding(3, &[0]);
+    /// let mut seen = Vec::new();
+    /// while let Some(p) = perms.advance() {
+    ///     seen.push(p.to_vec());
+    /// }
+    ///
+    /// assert_eq!(seen, [[2, 0, 1], [2, 1, 0], [1, 2, 0], [1, 0, 2]]);
+    /// ```
+    pub fn avoiding(n: usize, positions: &[usize]) -> Self {
+        let mut forbidden = vec![false; n];
+        for &i in positions {
+            assert!(i < n, "position out of range");
+            forbidden[i] = true;
+        }
+        Self::with_forbidden(forbidden)
+    }
+
+    fn with_forbidden(forbidden: Vec<bool>) -> Self {
+        let n = forbidden.len();
+        Self {
+            perms: Permutations::sjt((0..n).collect::<Vec<_>>()),
+            fixed: forbidden.iter().filter(|&&f| f).count(),
+            forbidden,
+            swaps: Vec::new(),
+        }
+    }
+
+    /// Moves to the next permutation without forbidden fixed points and
+    /// returns a view of it, or `None` once all have been visited.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        self.advance_with_swaps().map(|(p, _)| p)
+    }
+
+    /// Like [`Derangements::advance`], but also returns the adjacent swaps
+    /// `(i, i + 1)` that lead from the previous permutation (the identity for
+    /// the first one) to the new one, in the order in which they are applied
+    /// with `p.swap(i, i + 1)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::restricted::Derangements;
+    ///
+    /// let mut ders = Derangements::new(4);
+    /// let mut p = vec![0, 1, 2, 3];
+    /// while let Some((next, swaps)) = ders.advance_with_swaps() {
+    ///     for &(i, j) in swaps {
+    ///         p.swap(i, j);
+    ///     }
+    ///     assert_eq!(p, next);
+    /// }
+    /// ```
+    pub fn advance_with_swaps(&mut self) -> Option<(&[usize], &[Transposition])> {
+        self.swaps.clear();
+        loop {
+            let (p, swap) = self.perms.advance_with_swap()?;
+            if let Some((i, j)) = swap {
+                self.swaps.push((i, j));
+                // p[j] and p[i] were at positions i and j before the swap
+                for (pos, before, after) in [(i, p[j], p[i]), (j, p[i], p[j])] {
+                    if self.forbidden[pos] {
+                        self.fixed -= usize::from(before == pos);
+                        self.fixed += usize::from(after == pos);
+                    }
+                }
+            }
+            if self.fixed == 0 {
+                return Some((self.perms.as_slice(), &self.swaps));
+            }
+        }
+    }
+
+    /// Returns the current permutation.
+    pub fn as_slice(&self) -> &[usize] {
+        self.perms.as_slice()
+    }
+}
+
+/// Stateful generator of involutions and of permutations with a given cycle type
+///
+/// `CyclePermutations` visits every permutation of `{0, 1, ..., n-1}` whose
+/// cycle lengths satisfy the constraint exactly once. Permutations are
+/// produced in lexicographic order of their cycle notation, where each cycle
+/// starts with its smallest element and the cycles are sorted by that element.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::involution;
+/// use ecgen::restricted::CyclePermutations;
+///
+/// let mut invs = CyclePermutations::involutions(3);
+/// let mut seen = Vec::new();
+/// while let Some(p) = invs.advance() {
+///     seen.push(p.to_vec());
+/// }
+///
+/// assert_eq!(seen, [[0, 1, 2], [0, 2, 1], [1, 0, 2], [2, 1, 0]]);
+/// assert_eq!(seen.len(), involution(3));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "CyclePermutationsState")
+)]
+pub struct CyclePermutations {
+    perm: Vec<usize>,
+    /// The elements in cycle notation, without parentheses.
+    seq: Vec<usize>,
+    /// The length of the cycle starting at each position of `seq`.
+    len_at: Vec<usize>,
+    /// The positions in `seq` where a cycle starts.
+    starts: Vec<usize>,
+    used: Vec<bool>,
+    /// The number of cycles of each length still to be placed (`None` for unlimited).
+    avail: Vec<Option<usize>>,
+    started: bool,
+    /// The inverse of `perm`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pos: Vec<usize>,
+    /// The swaps made by the last call to `advance`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    swaps: Vec<Transposition>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct CyclePermutationsState {
+    perm: Vec<usize>,
+    seq: Vec<usize>,
+    len_at: Vec<usize>,
+    starts: Vec<usize>,
+    used: Vec<bool>,
+    avail: Vec<Option<usize>>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<CyclePermutationsState> for CyclePermutations {
+    type Error = &'static str;
+
+    fn try_from(state: CyclePermutationsState) -> Result<Self, Self::Error> {
+        let CyclePermutationsState {
+            perm,
+            seq,
+            len_at,
+            starts,
+            used,
+            avail,
+            started,
+        } = state;
+        let n = perm.len();
+        let mut valid =
+            is_perm(&perm) && len_at.len() == n && used.len() == n && avail.len() == n + 1;
+        // either the involutions, or a cycle type whose lengths add up to `n`
+        // while no cycle is placed
+        let involutions = avail.iter().any(Option::is_none);
+        valid = valid
+            && if involutions {
+                avail
+                    .iter()
+                    .enumerate()
+                    .all(|(len, &c)| c == (!(1..=2).contains(&len)).then_some(0))
+            } else {
+                avail[0] == Some(0)
+            };
+        if valid && seq.is_empty() {
+            // not started yet (from the identity), or all visited
+            let identity = perm.iter().enumerate().all(|(x, &y)| x == y);
+            let total = avail
+                .iter()
+                .enumerate()
+                .try_fold(0usize, |total, (len, &c)| {
+                    c.unwrap_or(0).checked_mul(len)?.checked_add(total)
+                });
+            valid = starts.is_empty()
+                && used.iter().all(|&u| !u)
+                && (started || identity)
+                && (involutions || total == Some(n));
+        } else if valid {
+            // a complete permutation in cycle notation, each cycle starting
+            // with the smallest element left
+            let mut end = 0;
+            valid = started
+                && seq.len() == n
+                && is_perm(&seq)
+                && used.iter().all(|&u| u)
+                && (involutions || avail.iter().all(|&c| c == Some(0)))
+                && starts.iter().all(|&s| {
+                    let len = len_at.get(s).copied().unwrap_or(0);
+                    let ok = s == end
+                        && len > 0
+                        && len <= n - s
+                        && (!involutions || avail[len].is_none())
+                        && seq[s..].iter().all(|&e| e >= seq[s])
+                        && (0..len).all(|k| perm[seq[s + k]] == seq[s + (k + 1) % len]);
+                    end = s + len;
+                    ok
+                })
+                && end == n;
+        }
+        if !valid {
+            return Err("invalid CyclePermutations state");
+        }
+        let mut pos = vec![0; n];
+        for (x, &y) in perm.iter().enumerate() {
+            pos[y] = x;
+        }
+        Ok(Self {
+            perm,
+            seq,
+            len_at,
+            starts,
+            used,
+            avail,
+            started,
+            pos,
+            swaps: Vec::new(),
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CyclePermutations {
+    /// Creates the involutions of `n` elements, i.e. the permutations whose
+    /// cycles have length one or two.
+    pub fn involutions(n: usize) -> Self {
+        let mut avail = vec![Some(0); n + 1];
+        for a in avail.iter_mut().take(3).skip(1) {
+            *a = None;
+        }
+        Self::with_avail(n, avail)
+    }
+
+    /// Creates the permutations whose cycles have exactly the given `lengths`,
+    /// in any order; the number of elements is the sum of the lengths.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a length is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::cycle_type_count;
+    /// use ecgen::restricted::CyclePermutations;
+    ///
+    /// let mut perms = CyclePermutations::with_cycle_type(&[2, 2]);
+    /// let mut seen = Vec::new();
+    /// while let Some(p) = perms.advance() {
+    ///     seen.push(p.to_vec());
+    /// }
+    ///
+    /// assert_eq!(seen, [[1, 0, 3, 2], [2, 3, 0, 1], [3, 2, 1, 0]]);
+    /// assert_eq!(seen.len(), cycle_type_count(&[2, 2]));
+    /// ```
+    pub fn with_cycle_type(lengths: &[usize]) -> Self {
+        let n = lengths.iter().sum();
+        let mut avail = vec![Some(0); n + 1];
+        for &len in lengths {
+            assert!(len > 0, "cycle lengths must be positive");
+            if let Some(c) = &mut avail[len] {
+                *c += 1;
+            }
+        }
+        Self::with_avail(n, avail)
+    }
+
+    fn with_avail(n: usize, avail: Vec<Option<usize>>) -> Self {
+        Self {
+            perm: (0..n).collect(),
+            seq: Vec::with_capacity(n),
+            len_at: vec![0; n],
+            starts: Vec::with_capacity(n),
+            used: vec![false; n],
+            avail,
+            started: false,
+            pos: (0..n).collect(),
+            swaps: Vec::new(),
+        }
+    }
+
+    /// Moves to the next permutation and returns a view of it, or `None` once
+    /// all have been visited.
+    pub fn advance(&mut self) -> Option<&[usize]> {
+        self.advance_with_swaps().map(|(p, _)| p)
+    }
+
+    /// Like [`CyclePermutations::advance`], but also returns swaps `(i, j)`
+    /// that lead from the previous permutation (the identity for the first
+    /// one) to the new one, in the order in which they are applied with
+    /// `p.swap(i, j)`. There are fewer than `n` of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ecgen::restricted::CyclePermutations;
+    ///
+    /// let mut perms = CyclePermutations::with_cycle_type(&[3, 1, 1]);
+    /// let mut p = vec![0, 1, 2, 3, 4];
+    /// while let Some((next, swaps)) = perms.advance_with_swaps() {
+    ///     for &(i, j) in swaps {
+    ///         p.swap(i, j);
+    ///     }
+    ///     assert_eq!(p, next);
+    /// }
+    /// ```
+    pub fn advance_with_swaps(&mut self) -> Option<(&[usize], &[Transposition])> {
+        self.swaps.clear();
+        if self.started {
+            if !self.backtrack() {
+                return None;
+            }
+        } else {
+            self.started = true;
+        }
+        self.fill();
+        for t in 0..self.starts.len() {
+            let s = self.starts[t];
+            let len = self.len_at[s];
+            for k in 0..len {
+                self.place(self.seq[s + k], self.seq[s + (k + 1) % len]);
+            }
+        }
+        Some((&self.perm, &self.swaps))
+    }
+
+    /// Brings `y` to position `x` with one swap, if it is not there already.
+    ///
+    /// Positions that already hold their new image are never swapped again,
+    /// since no other position needs the same image.
+    fn place(&mut self, x: usize, y: usize) {
+        let j = self.pos[y];
+        if j != x {
+            self.perm.swap(x, j);
+            self.pos[self.perm[j]] = j;
+            self.pos[y] = x;
+            self.swaps.push((x.min(j), x.max(j)));
+        }
+    }
+
+    /// Returns the current permutation.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Takes the shortest available cycle length of at least `from` that fits
+    /// after position `t`.
+    fn take_length(&mut self, from: usize, t: usize) -> Option<usize> {
+        let n = self.perm.len();
+        let len = (from..=n - t).find(|&len| self.avail[len] != Some(0))?;
+        if let Some(c) = &mut self.avail[len] {
+            *c -= 1;
+        }
+        Some(len)
+    }
+
+    fn next_unused(&self, from: usize) -> Option<usize> {
+        (from..self.perm.len()).find(|&e| !self.used[e])
+    }
+
+    fn push(&mut self, e: usize) {
+        self.used[e] = true;
+        self.seq.push(e);
+    }
+
+    /// Completes `seq` with the smallest choices.
+    fn fill(&mut self) {
+        while self.seq.len() < self.perm.len() {
+            let t = self.seq.len();
+            let end = self.starts.last().map_or(0, |&s| s + self.len_at[s]);
+            // the smallest unused element is always a valid choice
+            let e = self.next_unused(0).unwrap_or_default();
+            if t == end {
+                // both constructors allow completing with the shortest lengths
+                let len = self.take_length(1, t).unwrap_or_default();
+                self.starts.push(t);
+                self.len_at[t] = len;
+            }
+            self.push(e);
+        }
+    }
+
+    /// Replaces the last choice that can be increased, dropping the choices
+    /// after it; returns `false` if there is none.
+    fn backtrack(&mut self) -> bool {
+        while let Some(e) = self.seq.pop() {
+            self.used[e] = false;
+            let t = self.seq.len();
+            if self.starts.last() == Some(&t) {
+                self.starts.pop();
+                let len = self.len_at[t];
+                if let Some(c) = &mut self.avail[len] {
+                    *c += 1;
+                }
+                if let Some(len) = self.take_length(len + 1, t) {
+                    self.starts.push(t);
+                    self.len_at[t] = len;
+                    self.push(e);
+                    return true;
+                }
+            } else if let Some(e) = self.next_unused(e + 1) {
+                self.push(e);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::counting::{derangement, involution};
+    use crate::perm::factorial;
+    use std::collections::HashSet;
+
+    /// Replays the swaps onto the previous permutation, which must give the
+    /// next one.
+    fn replay(out: &mut Vec<Vec<usize>>, p: &[usize], swaps: &[Transposition]) {
+        let mut q = out
+            .last()
+            .cloned()
+            .unwrap_or_else(|| (0..p.len()).collect());
+        for &(i, j) in swaps {
+            q.swap(i, j);
+        }
+        assert_eq!(q, p);
+        out.push(q);
+    }
+
+    fn collect_derangements(mut ders: Derangements) -> Vec<Vec<usize>> {
+        let mut out = Vec::new();
+        while let Some((p, swaps)) = ders.advance_with_swaps() {
+            assert!(swaps.iter().all(|&(i, j)| j == i + 1));
+            replay(&mut out, p, swaps);
+        }
+        out
+    }
+
+    fn collect_cycles(mut perms: CyclePermutations) -> Vec<Vec<usize>> {
+        let mut out = Vec::new();
+        while let Some((p, swaps)) = perms.advance_with_swaps() {
+            assert!(swaps.len() < p.len().max(1));
+            replay(&mut out, p, swaps);
+        }
+        out
+    }
+
+    fn cycle_lengths(p: &[usize]) -> Vec<usize> {
+        let mut seen = vec![false; p.len()];
+        let mut lengths = Vec::new();
+        for i in 0..p.len() {
+            let mut len = 0;
+            let mut j = i;
+            while !seen[j] {
+                seen[j] = true;
+                j = p[j];
+                len += 1;
+            }
+            if len > 0 {
+                lengths.push(len);
+            }
+        }
+        lengths.sort_unstable();
+        lengths
+    }
+
+    #[test]
+    fn test_derangements() {
+        for n in 0..8 {
+            let all = collect_derangements(Derangements::new(n));
+            assert_eq!(all.len(), derangement(n));
+            let distinct: HashSet<_> = all.iter().collect();
+            assert_eq!(distinct.len(), all.len());
+            assert!(all
+                .iter()
+                .all(|p| p.iter().enumerate().all(|(i, &x)| x != i)));
+        }
+    }
+
+    #[test]
+    fn test_derangements_avoiding() {
+        let n = 6;
+        for positions in [&[][..], &[0], &[1, 4], &[0, 2, 3, 5]] {
+            let all = collect_derangements(Derangements::avoiding(n, positions));
+            // inclusion-exclusion over the forbidden fixed points
+            let m = positions.len();
+            let expected: isize = (0..=m)
+                .map(|k| {
+                    let term = (crate::comb(m, k) * factorial(n - k)) as isize;
+                    if k % 2 == 0 {
+                        term
+                    } else {
+                        -term
+                    }
+                })
+                .sum();
+            assert_eq!(all.len() as isize, expected);
+            let distinct: HashSet<_> = all.iter().collect();
+            assert_eq!(distinct.len(), all.len());
+            assert!(all.iter().all(|p| positions.iter().all(|&i| p[i] != i)));
+        }
+    }
+
+    #[test]
+    fn test_involutions() {
+        for n in 0..9 {
+            let all = collect_cycles(CyclePermutations::involutions(n));
+            assert_eq!(all.len(), involution(n));
+            let distinct: HashSet<_> = all.iter().collect();
+            assert_eq!(distinct.len(), all.len());
+            assert!(all.iter().all(|p| (0..n).all(|i| p[p[i]] == i)));
+        }
+    }
+
+    #[test]
+    fn test_cycle_type() {
+        for lengths in [
+            &[][..],
+            &[1],
+            &[4],
+            &[1, 1, 1],
+            &[2, 1],
+            &[1, 3, 1],
+            &[2, 2, 2],
+            &[3, 2, 2, 1],
+            &[4, 3],
+        ] {
+            let all = collect_cycles(CyclePermutations::with_cycle_type(lengths));
+            assert_eq!(all.len(), cycle_type_count(lengths));
+            let distinct: HashSet<_> = all.iter().collect();
+            assert_eq!(distinct.len(), all.len());
+            let mut expected = lengths.to_vec();
+            expected.sort_unstable();
+            assert!(all.iter().all(|p| cycle_lengths(p) == expected));
+        }
+    }
+
+    #[test]
+    fn test_cycle_type_count() {
+        // summing over all cycle types of 6 gives 6!
+        let types: [&[usize]; 11] = [
+            &[6],
+            &[5, 1],
+            &[4, 2],
+            &[4, 1, 1],
+            &[3, 3],
+            &[3, 2, 1],
+            &[3, 1, 1, 1],
+            &[2, 2, 2],
+            &[2, 2, 1, 1],
+            &[2, 1, 1, 1, 1],
+            &[1, 1, 1, 1, 1, 1],
+        ];
+        let total: usize = types.iter().map(|t| cycle_type_count(t)).sum();
+        assert_eq!(total, factorial(6));
+        assert_eq!(checked_cycle_type_count(&[1; 100]), Some(1));
+        assert_eq!(checked_cycle_type_count(&[2; 40]), None);
+        // the lengths add up to more than usize::MAX
+        assert_eq!(checked_cycle_type_count(&[usize::MAX, 1]), None);
+        assert_eq!(checked_cycle_type_count(&[usize::MAX / 2 + 1; 2]), None);
+    }
+}
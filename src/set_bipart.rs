@@ -7,7 +7,9 @@
 //! ## Key Functions
 //!
 //! - [`stirling2nd2`] - Calculate Stirling numbers of the second kind for k=2
+//! - [`checked_stirling2nd2`] / [`checked_stirling2nd2_u128`] - The same, reporting overflow
 //! - [`set_bipart_gen`] - Generate all bipartitions of an n-element set
+//! - [`SetBipartIter`] - Iterator over the same moves as [`set_bipart_gen`]
 //!
 //! ## Algorithm
 //!
@@ -17,7 +19,15 @@
 //! successive partitions differ by moving only one element.
 //!
 //! The total number of bipartitions of an n-element set is S(n,2) = 2^(n-1) - 1.
+//!
+//! [`SetBipartIter`] yields the same moves as [`set_bipart_gen`], running the
+//! recursion on an explicit stack instead of boxing a generator per level.
 
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::iter::FusedIterator;
+#[cfg(feature = "generator")]
 use genawaiter::sync::{Gen, GenBoxed};
 
 /// The `stirling2nd2` function calculates the Stirling number of the second kind specifically for k =
@@ -40,12 +50,109 @@ use genawaiter::sync::{Gen, GenBoxed};
 ///
 /// assert_eq!(stirling2nd2(5), 15);
 /// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; use [`checked_stirling2nd2`] to size an
+/// enumeration before starting it.
 #[inline]
 pub const fn stirling2nd2(n: usize) -> usize {
     if n <= 2 {
         1
     } else {
-        1 + 2 * stirling2nd2(n - 1)
+        match checked_stirling2nd2(n) {
+            Some(r) => r,
+            None => panic!("stirling2nd2: result overflows usize"),
+        }
+    }
+}
+
+/// The `checked_stirling2nd2` function calculates S(n, 2) = 2^(n-1) - 1, returning `None`
+/// instead of overflowing.
+///
+/// Unlike [`stirling2nd2`], this is the mathematical value, so it is `Some(0)` for `n < 2`.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// `Some(S(n, 2))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_stirling2nd2;
+///
+/// assert_eq!(checked_stirling2nd2(5), Some(15));
+/// assert_eq!(checked_stirling2nd2(usize::BITS as usize + 2), None);
+/// ```
+pub const fn checked_stirling2nd2(n: usize) -> Option<usize> {
+    match checked_stirling2nd2_u128(n as u128) {
+        Some(r) if r <= usize::MAX as u128 => Some(r as usize),
+        _ => None,
+    }
+}
+
+/// The `checked_stirling2nd2_u128` function calculates S(n, 2) = 2^(n-1) - 1 in `u128`,
+/// returning `None` instead of overflowing.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// `Some(S(n, 2))`, or `None` if it does not fit in a `u128` (that is, for `n > 129`).
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_stirling2nd2_u128;
+///
+/// assert_eq!(checked_stirling2nd2_u128(129), Some(u128::MAX));
+/// assert_eq!(checked_stirling2nd2_u128(130), None);
+/// ```
+pub const fn checked_stirling2nd2_u128(n: u128) -> Option<u128> {
+    if n < 2 {
+        Some(0)
+    } else if n - 1 > 128 {
+        None
+    } else if n - 1 == 128 {
+        Some(u128::MAX)
+    } else {
+        Some((1u128 << (n - 1)) - 1)
+    }
+}
+
+/// The `stirling2nd2_big` function calculates S(n, 2) = 2^(n-1) - 1 exactly.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// S(n, 2) as a [`BigUint`](num_bigint::BigUint).
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::stirling2nd2_big;
+///
+/// use ecgen::BigUint;
+///
+/// assert_eq!(stirling2nd2_big(130), BigUint::from(u128::MAX) * 2u32 + 1u32);
+/// ```
+#[cfg(feature = "bigint")]
+pub fn stirling2nd2_big(n: usize) -> num_bigint::BigUint {
+    use num_bigint::BigUint;
+
+    if n < 2 {
+        BigUint::from(0u32)
+    } else {
+        (BigUint::from(1u32) << (n - 1)) - 1u32
     }
 }
 
@@ -79,6 +186,7 @@ pub const fn stirling2nd2(n: usize) -> usize {
 ///
 /// assert_eq!(cnt, 15);
 /// ```
+#[cfg(feature = "generator")]
 pub fn set_bipart_gen(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| async move {
         for i in gen0_even(n) {
@@ -100,6 +208,7 @@ pub fn set_bipart_gen(n: usize) -> GenBoxed<usize> {
 /// Returns:
 ///
 /// The function `gen0_even` returns a boxed generator (`GenBoxed<usize>`).
+#[cfg(feature = "generator")]
 #[inline]
 fn gen0_even(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| async move {
@@ -129,6 +238,7 @@ fn gen0_even(n: usize) -> GenBoxed<usize> {
 /// The function `gen1_even` returns a boxed generator that yields even numbers from 2 to `n`,
 /// inclusive, in a specific pattern.
 /// S(n,k,1) even k
+#[cfg(feature = "generator")]
 #[inline]
 fn gen1_even(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| async move {
@@ -159,6 +269,7 @@ fn gen1_even(n: usize) -> GenBoxed<usize> {
 /// The function `neg1_even` returns a boxed generator that yields even numbers from 2 to `n`,
 /// inclusive, in a specific pattern.
 /// S(n,k,1) even k
+#[cfg(feature = "generator")]
 #[inline]
 fn neg1_even(n: usize) -> GenBoxed<usize> {
     Gen::new_boxed(|co| async move {
@@ -176,10 +287,201 @@ fn neg1_even(n: usize) -> GenBoxed<usize> {
     })
 }
 
+/// The three mutually recursive lists `gen0_even`, `gen1_even` and `neg1_even`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum BpKind {
+    Gen0,
+    Gen1,
+    Neg1,
+}
+
+/// One step of a list body: either yield a move or call a list on `n - 1`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy)]
+enum BpOp {
+    Yield(usize),
+    Call(BpKind),
+}
+
+#[cfg(feature = "alloc")]
+impl BpKind {
+    /// The body of the list for `n >= 3`; the lists are empty for `n < 3`.
+    fn body(self, n: usize) -> [BpOp; 4] {
+        use BpKind::*;
+        use BpOp::*;
+        match self {
+            Gen0 => [Yield(n - 1), Call(Gen1), Yield(n), Call(Neg1)],
+            Gen1 => [Yield(2), Call(Neg1), Yield(n), Call(Gen1)],
+            Neg1 => [Call(Neg1), Yield(n), Call(Gen1), Yield(2)],
+        }
+    }
+}
+
+/// Iterator over the moves of the set bipartition Gray code
+///
+/// `SetBipartIter` yields exactly the same moves as [`set_bipart_gen`]: each
+/// item `x` moves element `x` (counting from 1) to the other block, starting
+/// from `0^(n-1) 1`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::set_bipart::SetBipartIter;
+///
+/// const N: usize = 5;
+///
+/// // 0 0 0 0 0 1
+/// let mut b = [0; N + 1];
+/// b[N] = 1; // b[0] is unused
+/// let mut cnt = 1;
+/// for x in SetBipartIter::new(N) {
+///     b[x] = 1 - b[x];
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 15);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SetBipartState")
+)]
+pub struct SetBipartIter {
+    /// Suspended lists as `(kind, n, index of the next step in the body)`.
+    stack: Vec<(BpKind, usize, usize)>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct SetBipartState {
+    stack: Vec<(BpKind, usize, usize)>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<SetBipartState> for SetBipartIter {
+    type Error = &'static str;
+
+    fn try_from(state: SetBipartState) -> Result<Self, Self::Error> {
+        let SetBipartState { stack, remaining } = state;
+        // every list below the top one has just called the list above it
+        let valid = stack
+            .first()
+            .map_or(true, |&(kind, _, _)| kind == BpKind::Gen0)
+            && stack.iter().all(|&(_, _, pc)| pc <= 4)
+            && stack.windows(2).all(|w| {
+                let ((kind, n, pc), (callee, m, _)) = (w[0], w[1]);
+                n >= 3
+                    && pc > 0
+                    && m == n - 1
+                    && matches!(kind.body(n)[pc - 1], BpOp::Call(c) if c == callee)
+            });
+        if !valid {
+            return Err("invalid SetBipartIter state");
+        }
+        Ok(Self { stack, remaining })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SetBipartIter {
+    /// Creates the iterator for the bipartitions of an n-element set.
+    ///
+    /// The iterator is empty for `n < 3`.
+    pub fn new(n: usize) -> Self {
+        let remaining = if n < 3 {
+            Some(0)
+        } else {
+            checked_stirling2nd2(n).map(|r| r - 1)
+        };
+        Self {
+            stack: vec![(BpKind::Gen0, n, 0)],
+            remaining,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for SetBipartIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            let (kind, n, pc) = self.stack.last_mut()?;
+            if *n < 3 || *pc == 4 {
+                self.stack.pop();
+                continue;
+            }
+            let op = kind.body(*n)[*pc];
+            *pc += 1;
+            match op {
+                BpOp::Yield(x) => {
+                    self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+                    return Some(x);
+                }
+                BpOp::Call(kind) => {
+                    let n = *n - 1;
+                    self.stack.push((kind, n, 0));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for SetBipartIter {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::set_partition::checked_stirling2nd_u128;
+
+    #[test]
+    fn test_stirling2nd2() {
+        assert_eq!(stirling2nd2(2), 1);
+        assert_eq!(stirling2nd2(3), 3);
+        assert_eq!(stirling2nd2(10), 511);
+    }
+
+    #[test]
+    fn test_checked_stirling2nd2() {
+        for n in 0..=129u128 {
+            let expected = checked_stirling2nd_u128(n, 2);
+            assert_eq!(checked_stirling2nd2_u128(n), expected);
+        }
+        assert_eq!(checked_stirling2nd2_u128(130), None);
+        assert_eq!(checked_stirling2nd2(1), Some(0));
+        assert_eq!(checked_stirling2nd2(5), Some(stirling2nd2(5)));
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(checked_stirling2nd2(64), Some((1 << 63) - 1));
+            assert_eq!(checked_stirling2nd2(65), Some(usize::MAX));
+            assert_eq!(checked_stirling2nd2(66), None);
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_stirling2nd2_big() {
+        for n in 0..=129 {
+            let r = checked_stirling2nd2_u128(n as u128).unwrap();
+            assert_eq!(stirling2nd2_big(n), num_bigint::BigUint::from(r));
+        }
+    }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_bipart_odd() {
         const N: usize = 11;
@@ -197,6 +499,7 @@ mod tests {
         assert_eq!(cnt, stirling2nd2(N));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_bipart_even() {
         const N: usize = 10;
@@ -216,6 +519,7 @@ mod tests {
 
     /// The function `test_set_bipart_special` tests the correctness of the `set_bipart_gen` function by
     /// comparing its output with the `stirling2nd2` function.
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_bipart_special() {
         const N: usize = 2;
@@ -226,4 +530,20 @@ mod tests {
         }
         assert_eq!(cnt, stirling2nd2(N));
     }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_set_bipart_iter_matches_generator() {
+        for n in 0..14 {
+            assert!(SetBipartIter::new(n).eq(set_bipart_gen(n)), "n={n}");
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_bipart_iter_size_hint() {
+        let iter = SetBipartIter::new(10);
+        assert_eq!(iter.size_hint(), (510, Some(510)));
+        assert_eq!(iter.count(), stirling2nd2(10) - 1);
+    }
 }
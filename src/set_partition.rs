@@ -48,10 +48,24 @@
 ///
 /// ## Complexity
 ///
-/// - `stirling2nd(n, k)`: O(k) time, O(k) stack space
+/// - `stirling2nd(n, k)`: O(min(k, n-k) (n-k)) time, O(1) space
 /// - `set_partition_gen(n, k)`: O(S(n,k)) output size, O(k) auxiliary space
+/// - `SetPartitionIter::new(n, k)`: the same moves on an explicit stack, O(n) space
+/// - `set_partition_all_gen(n)`: all B(n) partitions in Gray order, O(n^2) auxiliary space
+/// - `SetPartitionAllIter::new(n)`: the same moves, O(n) time per move, O(n) space
+/// - `SetPartition`: the moves applied to an owned partition, O(n) time per move, O(n) space
+#[cfg(feature = "generator")]
 use genawaiter::sync::{Gen, GenBoxed};
 
+use crate::combin::checked_comb_u128;
+#[cfg(feature = "alloc")]
+use crate::counting::checked_bell;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::iter::FusedIterator;
+
 /// Stirling number of second kind.
 ///
 /// The `stirling2nd` function calculates the Stirling number of the second kind for given values of `n`
@@ -76,25 +90,232 @@ use genawaiter::sync::{Gen, GenBoxed};
 ///
 /// assert_eq!(stirling2nd(5, 3), 25);
 /// ```
+///
+/// # Panics
+///
+/// Panics if the result does not fit in a `usize`; use [`checked_stirling2nd`] to size an
+/// enumeration before starting it.
 #[inline]
 pub const fn stirling2nd(n: usize, k: usize) -> usize {
     if k >= n || k <= 1 {
         1
     } else {
-        stirling2nd_recur(n, k)
+        match checked_stirling2nd(n, k) {
+            Some(r) => r,
+            None => panic!("stirling2nd: result overflows usize"),
+        }
     }
 }
 
-#[inline]
-const fn stirling2nd_recur(n: usize, k: usize) -> usize {
-    let n = n - 1;
-    let a = if k == 2 {
-        1
-    } else {
-        stirling2nd_recur(n, k - 1)
-    };
-    let b = if k == n { 1 } else { stirling2nd_recur(n, k) };
-    a + k * b
+/// The `checked_stirling2nd` function calculates the Stirling number of the second kind S(n, k),
+/// returning `None` instead of overflowing.
+///
+/// Unlike [`stirling2nd`], this is the mathematical value, so it is `Some(0)` for `k > n` and
+/// for `k == 0 < n`.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+/// * `k`: The number of non-empty blocks.
+///
+/// Returns:
+///
+/// `Some(S(n, k))`, or `None` if it does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_stirling2nd;
+///
+/// assert_eq!(checked_stirling2nd(5, 3), Some(25));
+/// assert_eq!(checked_stirling2nd(3, 5), Some(0));
+/// assert_eq!(checked_stirling2nd(100, 50), None);
+/// ```
+pub const fn checked_stirling2nd(n: usize, k: usize) -> Option<usize> {
+    match checked_stirling2nd_u128(n as u128, k as u128) {
+        Some(r) if r <= usize::MAX as u128 => Some(r as usize),
+        _ => None,
+    }
+}
+
+/// The `checked_stirling2nd_u128` function calculates the Stirling number of the second kind
+/// S(n, k) in `u128`, returning `None` instead of overflowing.
+///
+/// With m = n - k, the value is computed in O(min(k, m) * m) time, independent of how large
+/// n is: by dynamic programming over S(i + j, i) for i <= k and j <= m when k <= m, and from
+/// the second-order Eulerian numbers otherwise. Every intermediate value is no larger than
+/// the result, so `None` is returned only when S(n, k) really exceeds `u128::MAX`. Since
+/// S(n, k) >= 2^(n-k) for k >= 2, the tables never need more than 128 entries and no
+/// allocation is made.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+/// * `k`: The number of non-empty blocks.
+///
+/// Returns:
+///
+/// `Some(S(n, k))`, or `None` if it does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::checked_stirling2nd_u128;
+///
+/// assert_eq!(checked_stirling2nd_u128(30, 10), Some(173_373_343_599_189_364_594_756));
+/// assert_eq!(checked_stirling2nd_u128(140, 2), None);
+/// ```
+pub const fn checked_stirling2nd_u128(n: u128, k: u128) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    if k == n {
+        return Some(1);
+    }
+    if k == 0 {
+        return Some(0);
+    }
+    if k == 1 {
+        return Some(1);
+    }
+    let m = n - k;
+    if m >= 128 {
+        return None;
+    }
+    if m == 1 {
+        return checked_comb_u128(n, 2);
+    }
+    if k > m {
+        return checked_stirling_eulerian_u128(n, m as usize, false);
+    }
+    let m = m as usize;
+    // row[j] = S(i + j, i) for the current i
+    let mut row = [0u128; 128];
+    row[0] = 1;
+    let mut i: u128 = 1;
+    while i <= k {
+        let mut j = 1;
+        while j <= m {
+            row[j] = match row[j - 1].checked_mul(i) {
+                Some(v) => match v.checked_add(row[j]) {
+                    Some(v) => v,
+                    None => return None,
+                },
+                None => return None,
+            };
+            j += 1;
+        }
+        i += 1;
+    }
+    Some(row[m])
+}
+
+/// S(n, n - m), or c(n, n - m) if `first_kind`, in `u128` from the second-order Eulerian
+/// numbers <<m, j>>, in O(m^2) time:
+///
+/// S(n, n - m) = sum over j of <<m, j>> C(n + m - 1 - j, 2m)
+/// c(n, n - m) = sum over j of <<m, j>> C(n + j, 2m)
+///
+/// Requires `m < 128` and `n - m > m`. Then every term is non-zero, and as <<i, j>> grows
+/// with i, no intermediate value exceeds the result.
+pub(crate) const fn checked_stirling_eulerian_u128(
+    n: u128,
+    m: usize,
+    first_kind: bool,
+) -> Option<u128> {
+    // row[j] = <<i, j>> for the current i
+    let mut row = [0u128; 128];
+    row[0] = 1;
+    let mut i = 1;
+    while i <= m {
+        // <<i, j>> = (j + 1) <<i - 1, j>> + (2i - 1 - j) <<i - 1, j - 1>>
+        let mut j = i;
+        while j > 0 {
+            j -= 1;
+            let stay = match row[j].checked_mul(j as u128 + 1) {
+                Some(v) => v,
+                None => return None,
+            };
+            let step = if j == 0 {
+                0
+            } else {
+                match row[j - 1].checked_mul((2 * i - 1 - j) as u128) {
+                    Some(v) => v,
+                    None => return None,
+                }
+            };
+            row[j] = match stay.checked_add(step) {
+                Some(v) => v,
+                None => return None,
+            };
+        }
+        i += 1;
+    }
+    let mut sum: u128 = 0;
+    let mut j = 0;
+    while j < m {
+        let top = if first_kind {
+            n.checked_add(j as u128)
+        } else {
+            n.checked_add((m - 1 - j) as u128)
+        };
+        let binom = match top {
+            Some(top) => checked_comb_u128(top, 2 * m as u128),
+            None => None,
+        };
+        let term = match binom {
+            Some(b) => row[j].checked_mul(b),
+            None => None,
+        };
+        sum = match term {
+            Some(t) => match sum.checked_add(t) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        j += 1;
+    }
+    Some(sum)
+}
+
+/// The `stirling2nd_big` function calculates the Stirling number of the second kind S(n, k)
+/// exactly.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+/// * `k`: The number of non-empty blocks.
+///
+/// Returns:
+///
+/// S(n, k) as a [`BigUint`](num_bigint::BigUint).
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::stirling2nd_big;
+///
+/// assert_eq!(stirling2nd_big(5, 3).to_string(), "25");
+/// ```
+#[cfg(feature = "bigint")]
+pub fn stirling2nd_big(n: usize, k: usize) -> num_bigint::BigUint {
+    use num_bigint::BigUint;
+
+    if k > n || (k == 0 && n > 0) {
+        return BigUint::from(0u32);
+    }
+    let m = n - k;
+    // row[j] = S(i + j, i) for the current i
+    let mut row = vec![BigUint::from(0u32); m + 1];
+    row[0] = BigUint::from(1u32);
+    for i in 1..=k {
+        for j in 1..=m {
+            let v = &row[j - 1] * i + &row[j];
+            row[j] = v;
+        }
+    }
+    row.swap_remove(m)
 }
 
 /// The lists S(n,k,0) and S(n,k,1) satisfy the following properties.
@@ -166,6 +387,7 @@ const fn stirling2nd_recur(n: usize, k: usize) -> usize {
 ///
 /// assert_eq!(cnt, 90);
 /// ```
+#[cfg(feature = "generator")]
 pub fn set_partition_gen(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if !(k > 1 && k < n) {
@@ -184,6 +406,7 @@ pub fn set_partition_gen(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S(n,k,0) even k
+#[cfg(feature = "generator")]
 fn gen0_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| {
         async move {
@@ -223,6 +446,7 @@ fn gen0_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S'(n,k,0) even k
+#[cfg(feature = "generator")]
 fn neg0_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| {
         async move {
@@ -263,6 +487,7 @@ fn neg0_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S(n,k,1) even k
+#[cfg(feature = "generator")]
 fn gen1_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k > 3 {
@@ -300,6 +525,7 @@ fn gen1_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S'(n,k,1) even k
+#[cfg(feature = "generator")]
 fn neg1_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k < n - 1 {
@@ -337,6 +563,7 @@ fn neg1_even(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S(n,k,0) odd k
+#[cfg(feature = "generator")]
 fn gen0_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         for (i, j) in gen1_even(n - 1, k - 1) {
@@ -367,6 +594,7 @@ fn gen0_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S'(n,k,0) odd k
+#[cfg(feature = "generator")]
 fn neg0_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k < n - 1 {
@@ -397,6 +625,7 @@ fn neg0_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S(n,k,1) odd k
+#[cfg(feature = "generator")]
 fn gen1_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         for (i, j) in gen0_even(n - 1, k - 1) {
@@ -427,6 +656,7 @@ fn gen1_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
 }
 
 /// S'(n,k,1) odd k
+#[cfg(feature = "generator")]
 fn neg1_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     Gen::new_boxed(|co| async move {
         if k < n - 1 {
@@ -456,10 +686,882 @@ fn neg1_odd(n: usize, k: usize) -> GenBoxed<(usize, usize)> {
     })
 }
 
+/// Generate all set partitions by moving one element at a time
+///
+/// The `set_partition_all_gen` function enumerates every partition of the set {1..n}, whatever
+/// the number of blocks, so that successive partitions differ by moving a single element.
+///
+/// The partitions are listed by their restricted growth strings. The list for n is built from
+/// the list for n-1: behind the i-th prefix (with largest block `m`) the last element runs
+/// through the blocks `0, m+1, m, ..., 1` when i is even and `1, 2, ..., m+1, 0` when i is odd,
+/// so the seams between prefixes also change only one position. The first partition is
+/// `0^n`, i.e. the single block {1..n}.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements in the set.
+///
+/// Returns:
+///
+/// The function returns a `GenBoxed<(usize, usize)>` yielding moves `(x, y)`: element `x`
+/// (counting from 1) moves to block `y`. There are `bell(n) - 1` moves.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::{bell, set_partition_all_gen};
+///
+/// const N: usize = 5;
+///
+/// // 0 0 0 0 0
+/// let mut b = [0; N + 1];
+/// let mut cnt = 1;
+/// for (x, y) in set_partition_all_gen(N) {
+///     let old = b[x];
+///     b[x] = y;
+///     println!("Move {x} from Block {old} to Block {y}");
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, bell(N));
+/// ```
+#[cfg(feature = "generator")]
+pub fn set_partition_all_gen(n: usize) -> GenBoxed<(usize, usize)> {
+    Gen::new_boxed(|co| async move {
+        if n < 2 {
+            return;
+        }
+        // restricted growth string of the first n-1 elements
+        let mut prefix = vec![0; n - 1];
+        let mut even = true;
+        let mut sub = set_partition_all_gen(n - 1).into_iter();
+        loop {
+            let m = prefix.iter().copied().max().unwrap_or(0);
+            for p in 1..=m + 1 {
+                co.yield_((n, last_block(even, m, p))).await;
+            }
+            match sub.next() {
+                Some((x, y)) => {
+                    prefix[x - 1] = y;
+                    co.yield_((x, y)).await;
+                    even = !even;
+                }
+                None => break,
+            }
+        }
+    })
+}
+
+/// Block of the last element after `p` steps of its sweep behind a prefix whose largest
+/// block is `m`: `0, m+1, m, ..., 1` for even prefixes and `1, 2, ..., m+1, 0` for odd ones.
+#[cfg(feature = "alloc")]
+#[inline]
+fn last_block(even: bool, m: usize, p: usize) -> usize {
+    match (even, p) {
+        (true, 0) => 0,
+        (true, p) => m + 2 - p,
+        (false, p) if p <= m => p + 1,
+        (false, _) => 0,
+    }
+}
+
+/// The eight mutually recursive lists S(n,k,p) and S'(n,k,p) of Ruskey's algorithm.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum SpKind {
+    Gen0Even,
+    Neg0Even,
+    Gen1Even,
+    Neg1Even,
+    Gen0Odd,
+    Neg0Odd,
+    Gen1Odd,
+    Neg1Odd,
+}
+
+/// The shape shared by the lists: `gen*` lists first call `prefix` on
+/// `(n-1, k-1)`, yield `head` and then sweep element `n` through the blocks
+/// downwards, interleaved with calls `c` and `d` on `(n-1, k)`; `neg*` lists
+/// sweep element `n` upwards, interleaved with calls `a` and `b` on `(n-1, k)`,
+/// then yield `tail` and finally call `suffix` on `(n-1, k-1)`.
+#[cfg(feature = "alloc")]
+struct SpShape {
+    even: bool,
+    edge: Option<SpKind>,
+    head: (usize, usize),
+    first: SpKind,
+    second: SpKind,
+}
+
+#[cfg(feature = "alloc")]
+impl SpKind {
+    fn is_gen(self) -> bool {
+        matches!(
+            self,
+            SpKind::Gen0Even | SpKind::Gen1Even | SpKind::Gen0Odd | SpKind::Gen1Odd
+        )
+    }
+
+    /// `edge` is the prefix of a `gen*` list or the suffix of a `neg*` list;
+    /// `head` is the head of a `gen*` list or the tail of a `neg*` list; and
+    /// `first`/`second` are the calls `c`/`d` or `a`/`b` of the sweep.
+    fn shape(self, n: usize, k: usize) -> SpShape {
+        use SpKind::*;
+        let (even, edge, head, first, second) = match self {
+            Gen0Even => (
+                true,
+                (k > 2).then_some(Gen0Odd),
+                (n - 1, k - 1),
+                Gen1Even,
+                Neg1Even,
+            ),
+            Gen1Even => (
+                true,
+                (k > 3).then_some(Gen1Odd),
+                (k, k - 1),
+                Neg1Even,
+                Gen1Even,
+            ),
+            Gen0Odd => (false, Some(Gen1Even), (k, k - 1), Gen1Odd, Neg1Odd),
+            Gen1Odd => (false, Some(Gen0Even), (n - 1, k - 1), Neg1Odd, Gen1Odd),
+            Neg0Even => (
+                true,
+                (k > 3).then_some(Neg0Odd),
+                (n - 1, 0),
+                Gen1Even,
+                Neg1Even,
+            ),
+            Neg1Even => (true, (k > 3).then_some(Neg1Odd), (k, 0), Neg1Even, Gen1Even),
+            Neg0Odd => (false, Some(Neg1Even), (k, 0), Gen1Odd, Neg1Odd),
+            Neg1Odd => (false, Some(Neg0Even), (n - 1, 0), Neg1Odd, Gen1Odd),
+        };
+        SpShape {
+            even,
+            edge,
+            head,
+            first,
+            second,
+        }
+    }
+}
+
+/// A suspended call of one of the lists: `pc` marks where to resume and `i` is
+/// the block that element `n` is currently swept through.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SpFrame {
+    kind: SpKind,
+    n: usize,
+    k: usize,
+    pc: u8,
+    i: usize,
+}
+
+#[cfg(feature = "alloc")]
+enum SpStep {
+    Yield((usize, usize)),
+    Call(SpKind, usize, usize),
+    Return,
+}
+
+#[cfg(feature = "alloc")]
+impl SpFrame {
+    fn new(kind: SpKind, n: usize, k: usize) -> Self {
+        Self {
+            kind,
+            n,
+            k,
+            pc: 0,
+            i: 0,
+        }
+    }
+
+    /// Runs the frame until it yields a move, calls a sublist or returns.
+    fn step(&mut self) -> SpStep {
+        if self.kind.is_gen() {
+            self.step_gen()
+        } else {
+            self.step_neg()
+        }
+    }
+
+    fn step_gen(&mut self) -> SpStep {
+        let (n, k) = (self.n, self.k);
+        let shape = self.kind.shape(n, k);
+        let full = k < n - 1;
+        loop {
+            match self.pc {
+                0 => {
+                    self.pc = 1;
+                    if let Some(prefix) = shape.edge {
+                        return SpStep::Call(prefix, n - 1, k - 1);
+                    }
+                }
+                1 => {
+                    self.pc = 2;
+                    return SpStep::Yield(shape.head);
+                }
+                2 => {
+                    // even k enters the sweep at block k-2, odd k at block k-1
+                    if shape.even {
+                        self.i = k - 1;
+                        self.pc = if full { 4 } else { 5 };
+                    } else {
+                        self.i = k;
+                        self.pc = if full { 6 } else { 3 };
+                    }
+                }
+                3 => {
+                    if self.i < 3 {
+                        return SpStep::Return;
+                    }
+                    self.i -= 2;
+                    self.pc = if full { 4 } else { 5 };
+                    return SpStep::Yield((n, self.i));
+                }
+                4 => {
+                    self.pc = 5;
+                    return SpStep::Call(shape.first, n - 1, k);
+                }
+                5 => {
+                    self.pc = if full { 6 } else { 3 };
+                    return SpStep::Yield((n, self.i - 1));
+                }
+                6 => {
+                    self.pc = 3;
+                    return SpStep::Call(shape.second, n - 1, k);
+                }
+                _ => return SpStep::Return,
+            }
+        }
+    }
+
+    fn step_neg(&mut self) -> SpStep {
+        let (n, k) = (self.n, self.k);
+        let shape = self.kind.shape(n, k);
+        let full = k < n - 1;
+        loop {
+            match self.pc {
+                0 => {
+                    self.i = 1;
+                    self.pc = 1;
+                }
+                1 => {
+                    self.pc = 2;
+                    if full {
+                        return SpStep::Call(shape.first, n - 1, k);
+                    }
+                }
+                2 => {
+                    // odd k leaves the sweep after a call, even k after a move
+                    if !shape.even && self.i == k {
+                        self.pc = 5;
+                        continue;
+                    }
+                    self.pc = 3;
+                    return SpStep::Yield((n, self.i));
+                }
+                3 => {
+                    self.pc = 4;
+                    if full {
+                        return SpStep::Call(shape.second, n - 1, k);
+                    }
+                }
+                4 => {
+                    if shape.even && self.i == k - 1 {
+                        self.pc = 5;
+                        continue;
+                    }
+                    self.pc = 1;
+                    self.i += 2;
+                    return SpStep::Yield((n, self.i - 1));
+                }
+                5 => {
+                    self.pc = 6;
+                    return SpStep::Yield(shape.head);
+                }
+                6 => {
+                    self.pc = 7;
+                    if let Some(suffix) = shape.edge {
+                        return SpStep::Call(suffix, n - 1, k - 1);
+                    }
+                }
+                _ => return SpStep::Return,
+            }
+        }
+    }
+
+    /// Whether `stack` is a chain of calls starting with the call `root`: each
+    /// frame is at a point that its list reaches, and each frame below the top
+    /// one has just made the call of the frame above it.
+    #[cfg(feature = "serde")]
+    fn is_call_chain(stack: &[Self], root: (SpKind, usize, usize)) -> bool {
+        let mut call = Some(root);
+        for (depth, frame) in stack.iter().enumerate() {
+            if call != Some((frame.kind, frame.n, frame.k)) {
+                return false;
+            }
+            let top = depth + 1 == stack.len();
+            let mut run = Self::new(frame.kind, frame.n, frame.k);
+            let mut made = None;
+            call = loop {
+                if (run.pc, run.i) == (frame.pc, frame.i) && (top || made.is_some()) {
+                    break made;
+                }
+                made = match run.step() {
+                    SpStep::Yield(_) => None,
+                    SpStep::Call(kind, n, k) => Some((kind, n, k)),
+                    SpStep::Return => return false,
+                };
+            };
+        }
+        true
+    }
+}
+
+/// Iterator over the moves of the set partition Gray code
+///
+/// `SetPartitionIter` yields exactly the same moves as [`set_partition_gen`]:
+/// each item `(x, y)` moves element `x` (counting from 1) to block `y`,
+/// starting from the restricted growth string `0^(n-k) 0 1 2 ... (k-1)`. The
+/// mutual recursion of the eight lists S(n,k,p) and S'(n,k,p) is run on an
+/// explicit stack of frames.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::set_partition::SetPartitionIter;
+///
+/// const N: usize = 5;
+/// const K: usize = 3;
+///
+/// // 0 0 0 1 2
+/// let mut b = [0; N + 1];
+/// let offset = N - K + 1;
+/// for i in 1..K {
+///     b[offset + i] = i;
+/// }
+/// let mut cnt = 1;
+/// for (x, y) in SetPartitionIter::new(N, K) {
+///     b[x] = y;
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 25);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SetPartitionIterState")
+)]
+pub struct SetPartitionIter {
+    stack: Vec<SpFrame>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct SetPartitionIterState {
+    stack: Vec<SpFrame>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<SetPartitionIterState> for SetPartitionIter {
+    type Error = &'static str;
+
+    fn try_from(state: SetPartitionIterState) -> Result<Self, Self::Error> {
+        let SetPartitionIterState { stack, remaining } = state;
+        let valid = stack.first().map_or(true, |root| {
+            let (n, k) = (root.n, root.k);
+            let kind = if k % 2 == 0 {
+                SpKind::Gen0Even
+            } else {
+                SpKind::Gen0Odd
+            };
+            k > 1 && k < n && SpFrame::is_call_chain(&stack, (kind, n, k))
+        });
+        if !valid {
+            return Err("invalid SetPartitionIter state");
+        }
+        Ok(Self { stack, remaining })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SetPartitionIter {
+    /// Creates the iterator for the partitions of an n-element set into `k` blocks.
+    ///
+    /// The iterator is empty unless `1 < k < n`.
+    pub fn new(n: usize, k: usize) -> Self {
+        if !(k > 1 && k < n) {
+            return Self {
+                stack: Vec::new(),
+                remaining: Some(0),
+            };
+        }
+        let kind = if k % 2 == 0 {
+            SpKind::Gen0Even
+        } else {
+            SpKind::Gen0Odd
+        };
+        Self {
+            stack: vec![SpFrame::new(kind, n, k)],
+            remaining: checked_stirling2nd(n, k).map(|r| r - 1),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for SetPartitionIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.stack.last_mut()?.step() {
+                SpStep::Yield(mv) => {
+                    self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+                    return Some(mv);
+                }
+                SpStep::Call(kind, n, k) => self.stack.push(SpFrame::new(kind, n, k)),
+                SpStep::Return => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for SetPartitionIter {}
+
+/// Iterator over the moves of the Gray code for all set partitions
+///
+/// `SetPartitionAllIter` yields exactly the same moves as
+/// [`set_partition_all_gen`], starting from the single block `0^n`. Instead of
+/// nesting one generator per element it keeps, for every position of the
+/// restricted growth string, the direction and progress of its sweep; a move
+/// advances the rightmost unfinished sweep and restarts all sweeps to its right
+/// in the opposite direction.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::{bell, SetPartitionAllIter};
+///
+/// const N: usize = 4;
+///
+/// let mut b = [0; N + 1];
+/// let mut cnt = 1;
+/// for (x, y) in SetPartitionAllIter::new(N) {
+///     b[x] = y;
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, bell(N));
+/// // the last partition is 0101, i.e. {1,3}, {2,4}
+/// assert_eq!(b[1..], [0, 1, 0, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SetPartitionAllState")
+)]
+pub struct SetPartitionAllIter {
+    rgs: Vec<usize>,
+    /// Whether the sweep of each position follows the even pattern.
+    even: Vec<bool>,
+    /// Largest block of the prefix while the sweep of each position runs.
+    max: Vec<usize>,
+    /// Steps taken in the current sweep of each position.
+    step: Vec<usize>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct SetPartitionAllState {
+    rgs: Vec<usize>,
+    even: Vec<bool>,
+    max: Vec<usize>,
+    step: Vec<usize>,
+    remaining: Option<usize>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<SetPartitionAllState> for SetPartitionAllIter {
+    type Error = &'static str;
+
+    fn try_from(state: SetPartitionAllState) -> Result<Self, Self::Error> {
+        let SetPartitionAllState {
+            rgs,
+            even,
+            max,
+            step,
+            remaining,
+        } = state;
+        let n = rgs.len();
+        let mut valid = even.len() == n && max.len() == n && step.len() == n;
+        // every position sits where its sweep behind the prefix has taken it
+        let mut m = 0;
+        for i in 1..n {
+            if !valid {
+                break;
+            }
+            m = m.max(rgs[i - 1]);
+            valid = max[i] == m && step[i] <= m + 1 && rgs[i] == last_block(even[i], m, step[i]);
+        }
+        if !valid || rgs.first().map_or(false, |&b| b != 0) {
+            return Err("invalid SetPartitionAllIter state");
+        }
+        Ok(Self {
+            rgs,
+            even,
+            max,
+            step,
+            remaining,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SetPartitionAllIter {
+    /// Creates the iterator for all partitions of an n-element set.
+    ///
+    /// The iterator is empty for `n < 2`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            rgs: vec![0; n],
+            even: vec![true; n],
+            max: vec![0; n],
+            step: vec![0; n],
+            remaining: checked_bell(n).map(|r| r - 1),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for SetPartitionAllIter {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        // position 0 is always in block 0
+        let i = (1..self.rgs.len())
+            .rev()
+            .find(|&i| self.step[i] <= self.max[i])?;
+        self.step[i] += 1;
+        let y = last_block(self.even[i], self.max[i], self.step[i]);
+        self.rgs[i] = y;
+        let mut m = self.rgs[..=i].iter().copied().max().unwrap_or(0);
+        for j in i + 1..self.rgs.len() {
+            self.even[j] = !self.even[j];
+            self.max[j] = m;
+            self.step[j] = 0;
+            m = m.max(self.rgs[j]);
+        }
+        self.remaining = self.remaining.map(|r| r.saturating_sub(1));
+        Some((i + 1, y))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(r) => (r, Some(r)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for SetPartitionAllIter {}
+
+/// Source of the moves applied by [`SetPartition`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum SpMoves {
+    Fixed(SetPartitionIter),
+    All(SetPartitionAllIter),
+}
+
+/// Stateful set partition driven by the Gray code moves
+///
+/// `SetPartition` owns the current partition of `{0, 1, ..., n-1}` as a
+/// restricted growth string and as a list of blocks, and applies each move of
+/// [`SetPartitionIter`] (for a fixed number of blocks) or
+/// [`SetPartitionAllIter`] (for any number of blocks) internally. Elements are
+/// counted from 0 here, unlike the moves of the underlying iterators.
+///
+/// Since the views borrow the state, `SetPartition` is advanced with
+/// [`SetPartition::advance`] rather than through the `Iterator` trait.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::set_partition::SetPartition;
+///
+/// let mut parts = SetPartition::new(4, 3);
+/// let mut cnt = 0;
+/// while let Some(p) = parts.advance() {
+///     assert_eq!(p.blocks().len(), 3);
+///     if let Some((x, from, to)) = p.last_move() {
+///         assert!(p.blocks()[to].contains(&x));
+///         assert!(!p.blocks()[from].contains(&x));
+///     }
+///     cnt += 1;
+/// }
+///
+/// assert_eq!(cnt, 6);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SetPartitionState")
+)]
+pub struct SetPartition {
+    rgs: Vec<usize>,
+    blocks: Vec<Vec<usize>>,
+    moves: SpMoves,
+    last_move: Option<(usize, usize, usize)>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[derive(serde::Deserialize)]
+struct SetPartitionState {
+    rgs: Vec<usize>,
+    blocks: Vec<Vec<usize>>,
+    moves: SpMoves,
+    last_move: Option<(usize, usize, usize)>,
+    started: bool,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl TryFrom<SetPartitionState> for SetPartition {
+    type Error = &'static str;
+
+    fn try_from(state: SetPartitionState) -> Result<Self, Self::Error> {
+        let SetPartitionState {
+            rgs,
+            blocks,
+            moves,
+            last_move,
+            started,
+        } = state;
+        let n = rgs.len();
+        // the restricted growth string must label the blocks in order
+        let mut m = 0;
+        let mut valid = rgs.iter().enumerate().all(|(x, &b)| {
+            let ok = b <= m && (x > 0 || b == 0);
+            m = m.max(b + 1);
+            ok
+        });
+        valid = valid
+            && blocks.len() == m
+            && blocks
+                .iter()
+                .enumerate()
+                .all(|(b, block)| (0..n).filter(|&x| rgs[x] == b).eq(block.iter().copied()))
+            && last_move.map_or(true, |(x, _, to)| x < n && rgs[x] == to)
+            && match &moves {
+                SpMoves::Fixed(iter) => iter
+                    .stack
+                    .first()
+                    .map_or(true, |root| root.n == n && root.k == m),
+                SpMoves::All(iter) => iter.rgs == rgs,
+            };
+        if !valid {
+            return Err("invalid SetPartition state");
+        }
+        Ok(Self {
+            rgs,
+            blocks,
+            moves,
+            last_move,
+            started,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SetPartition {
+    /// Creates the partitions of an n-element set into `k` blocks, in the order
+    /// of [`set_partition_gen`].
+    ///
+    /// The first partition visited is `0^(n-k) 0 1 ... (k-1)`.
+    ///
+    /// Arguments:
+    ///
+    /// * `n`: The parameter `n` represents the total number of elements in the set.
+    /// * `k`: The parameter `k` represents the number of blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`, or if `k == 0` while `n > 0`.
+    pub fn new(n: usize, k: usize) -> Self {
+        assert!(k <= n && (k > 0 || n == 0), "k must be in 1..=n");
+        let rgs: Vec<usize> = (0..n).map(|i| (i + k).saturating_sub(n)).collect();
+        Self::from_rgs(rgs, SpMoves::Fixed(SetPartitionIter::new(n, k)))
+    }
+
+    /// Creates all partitions of an n-element set, in the order of
+    /// [`set_partition_all_gen`].
+    ///
+    /// The first partition visited is the single block `0^n`.
+    ///
+    /// Arguments:
+    ///
+    /// * `n`: The parameter `n` represents the total number of elements in the set.
+    pub fn all(n: usize) -> Self {
+        Self::from_rgs(vec![0; n], SpMoves::All(SetPartitionAllIter::new(n)))
+    }
+
+    fn from_rgs(rgs: Vec<usize>, moves: SpMoves) -> Self {
+        let mut blocks: Vec<Vec<usize>> = Vec::new();
+        for (x, &b) in rgs.iter().enumerate() {
+            if b == blocks.len() {
+                blocks.push(Vec::new());
+            }
+            blocks[b].push(x);
+        }
+        Self {
+            rgs,
+            blocks,
+            moves,
+            last_move: None,
+            started: false,
+        }
+    }
+
+    /// Moves to the next partition and returns a view of it.
+    ///
+    /// The first call returns the initial partition; `None` is returned once
+    /// all partitions have been visited.
+    pub fn advance(&mut self) -> Option<&Self> {
+        if !self.started {
+            self.started = true;
+            return Some(self);
+        }
+        let (x, to) = match &mut self.moves {
+            SpMoves::Fixed(iter) => iter.next()?,
+            SpMoves::All(iter) => iter.next()?,
+        };
+        let x = x - 1;
+        let from = core::mem::replace(&mut self.rgs[x], to);
+        if let Ok(pos) = self.blocks[from].binary_search(&x) {
+            self.blocks[from].remove(pos);
+        }
+        if to >= self.blocks.len() {
+            self.blocks.resize_with(to + 1, Vec::new);
+        }
+        if let Err(pos) = self.blocks[to].binary_search(&x) {
+            self.blocks[to].insert(pos, x);
+        }
+        // only the block with the largest label can become empty
+        if matches!(self.blocks.last(), Some(b) if b.is_empty()) {
+            self.blocks.pop();
+        }
+        self.last_move = Some((x, from, to));
+        Some(self)
+    }
+
+    /// The current partition as a restricted growth string: entry `x` is the
+    /// block containing element `x`.
+    #[inline]
+    pub fn rgs(&self) -> &[usize] {
+        &self.rgs
+    }
+
+    /// The blocks of the current partition, each a sorted list of elements,
+    /// ordered by their smallest element.
+    #[inline]
+    pub fn blocks(&self) -> &[Vec<usize>] {
+        &self.blocks
+    }
+
+    /// The move `(element, from, to)` that produced the current partition, or
+    /// `None` for the initial partition.
+    #[inline]
+    pub fn last_move(&self) -> Option<(usize, usize, usize)> {
+        self.last_move
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_stirling2nd() {
+        assert_eq!(stirling2nd(5, 3), 25);
+        assert_eq!(stirling2nd(6, 3), 90);
+        assert_eq!(stirling2nd(10, 4), 34105);
+    }
+
+    #[test]
+    fn test_checked_stirling2nd() {
+        // Triangle S(n, k) in u128, saturating where it overflows
+        let mut row = [Some(0u128); 141];
+        row[0] = Some(1);
+        for n in 0..140u128 {
+            for k in 0..=n + 1 {
+                assert_eq!(checked_stirling2nd_u128(n, k), row[k as usize]);
+            }
+            for k in (1..=n as usize + 1).rev() {
+                row[k] = match (row[k], row[k - 1]) {
+                    (Some(a), Some(b)) => a.checked_mul(k as u128).and_then(|a| a.checked_add(b)),
+                    _ => None,
+                };
+            }
+            row[0] = Some(0);
+        }
+        assert_eq!(checked_stirling2nd(5, 3), Some(25));
+        assert_eq!(checked_stirling2nd(5, 0), Some(0));
+        assert_eq!(checked_stirling2nd(0, 0), Some(1));
+        assert_eq!(
+            checked_stirling2nd_u128(1 << 40, (1 << 40) - 1),
+            Some(((1 << 40) * ((1 << 40) - 1)) / 2)
+        );
+        assert_eq!(checked_stirling2nd_u128(1 << 70, (1 << 70) - 2), None);
+        // S(n, n - 2) = C(n + 1, 4) + 2 C(n, 4), without a loop over k
+        let n: u128 = 1 << 20;
+        let expected = checked_comb_u128(n + 1, 4).unwrap() + 2 * checked_comb_u128(n, 4).unwrap();
+        assert_eq!(checked_stirling2nd_u128(n, n - 2), Some(expected));
+        assert_eq!(checked_stirling2nd(1 << 30, (1 << 30) - 2), None);
+        assert_eq!(checked_stirling2nd_u128(u128::MAX, u128::MAX - 3), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_stirling2nd_big() {
+        for n in 0..60 {
+            for k in 0..=n + 1 {
+                if let Some(r) = checked_stirling2nd_u128(n as u128, k as u128) {
+                    assert_eq!(stirling2nd_big(n, k), num_bigint::BigUint::from(r));
+                }
+            }
+        }
+        assert_eq!(
+            stirling2nd_big(130, 2),
+            (num_bigint::BigUint::from(1u32) << 129u32) - 1u32
+        );
+    }
+
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_partition_odd_odd() {
         const N: usize = 11;
@@ -481,6 +1583,7 @@ mod tests {
         assert_eq!(cnt, stirling2nd(N, K));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_partition_even_odd() {
         const N: usize = 10;
@@ -502,6 +1605,7 @@ mod tests {
         assert_eq!(cnt, stirling2nd(N, K));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_partition_odd_even() {
         const N: usize = 11;
@@ -523,6 +1627,7 @@ mod tests {
         assert_eq!(cnt, stirling2nd(N, K));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_partition_even_even() {
         const N: usize = 10;
@@ -544,6 +1649,7 @@ mod tests {
         assert_eq!(cnt, stirling2nd(N, K));
     }
 
+    #[cfg(feature = "generator")]
     #[test]
     fn test_set_partition_special() {
         const N: usize = 6;
@@ -555,4 +1661,130 @@ mod tests {
         }
         assert_eq!(cnt, stirling2nd(N, K));
     }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_set_partition_iter_matches_generator() {
+        for n in 2..11 {
+            for k in 0..=n {
+                assert!(
+                    SetPartitionIter::new(n, k).eq(set_partition_gen(n, k)),
+                    "n={n}, k={k}"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_partition_iter_size_hint() {
+        let iter = SetPartitionIter::new(10, 4);
+        assert_eq!(iter.size_hint(), (34104, Some(34104)));
+        assert_eq!(iter.count(), stirling2nd(10, 4) - 1);
+        assert_eq!(SetPartitionIter::new(6, 6).count(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_partition_all_iter_visits_all() {
+        use std::collections::HashSet;
+
+        for n in 0..10 {
+            let mut b = vec![0; n + 1];
+            let mut seen = HashSet::new();
+            seen.insert(b.clone());
+            for (x, y) in SetPartitionAllIter::new(n) {
+                assert_ne!(b[x], y);
+                b[x] = y;
+                // still a restricted growth string
+                let mut m = 0;
+                for &c in &b[1..] {
+                    assert!(c <= m + 1);
+                    m = m.max(c);
+                }
+                assert!(seen.insert(b.clone()), "n={n} repeats {b:?}");
+            }
+            assert_eq!(seen.len(), crate::bell(n));
+        }
+    }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_set_partition_all_iter_matches_generator() {
+        for n in 0..10 {
+            assert!(
+                SetPartitionAllIter::new(n).eq(set_partition_all_gen(n)),
+                "n={n}"
+            );
+        }
+        let first: Vec<_> = SetPartitionAllIter::new(3).collect();
+        // 000 -> 001 -> 011 -> 012 -> 010
+        assert_eq!(first, [(3, 1), (2, 1), (3, 2), (3, 0)]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_partition_all_iter_size_hint() {
+        let iter = SetPartitionAllIter::new(10);
+        assert_eq!(iter.size_hint(), (115_974, Some(115_974)));
+        assert_eq!(iter.count(), 115_974);
+        assert_eq!(SetPartitionAllIter::new(1).count(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn check_blocks(p: &SetPartition) {
+        for (b, block) in p.blocks().iter().enumerate() {
+            assert!(!block.is_empty());
+            assert!(block.windows(2).all(|w| w[0] < w[1]));
+            assert!(block.iter().all(|&x| p.rgs()[x] == b));
+        }
+        let total: usize = p.blocks().iter().map(Vec::len).sum();
+        assert_eq!(total, p.rgs().len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_partition_state_visits_all() {
+        use std::collections::HashSet;
+
+        for n in 0..9 {
+            let ks = if n == 0 { 0..=0 } else { 1..=n };
+            for k in ks {
+                let mut parts = SetPartition::new(n, k);
+                let mut seen = HashSet::new();
+                while let Some(p) = parts.advance() {
+                    check_blocks(p);
+                    assert_eq!(p.blocks().len(), k);
+                    assert!(seen.insert(p.rgs().to_vec()));
+                }
+                assert_eq!(Some(seen.len()), checked_stirling2nd(n, k), "n={n}, k={k}");
+            }
+            let mut parts = SetPartition::all(n);
+            let mut seen = HashSet::new();
+            let mut prev = Vec::new();
+            while let Some(p) = parts.advance() {
+                check_blocks(p);
+                if let Some((x, from, to)) = p.last_move() {
+                    assert_eq!(prev[x], from);
+                    assert_eq!(p.rgs()[x], to);
+                }
+                prev = p.rgs().to_vec();
+                assert!(seen.insert(prev.clone()));
+            }
+            assert_eq!(seen.len(), crate::bell(n));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_set_partition_state_initial() {
+        let mut parts = SetPartition::new(5, 3);
+        let p = parts.advance().unwrap();
+        assert_eq!(p.rgs(), [0, 0, 0, 1, 2]);
+        assert_eq!(p.blocks(), [vec![0, 1, 2], vec![3], vec![4]]);
+        assert_eq!(p.last_move(), None);
+        let mut parts = SetPartition::new(3, 3);
+        assert_eq!(parts.advance().unwrap().rgs(), [0, 1, 2]);
+        assert!(parts.advance().is_none());
+    }
 }
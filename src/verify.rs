@@ -0,0 +1,588 @@
+//! Gray code property verification
+//!
+//! The generators in this crate claim that each successive configuration
+//! differs from the previous one by a single swap or move. The functions in
+//! this module replay a move sequence from the documented initial
+//! configuration and check these claims, returning a [`GrayReport`]:
+//!
+//! - [`verify_gray_code`] - bit flips, e.g. from [`BrgcIter`](crate::BrgcIter)
+//! - [`verify_combinations`] - swaps `(i, j)` of a 0/1 vector, e.g. from
+//!   [`EmkCombIter`](crate::EmkCombIter)
+//! - [`verify_permutations`] - transpositions `(i, j)` of positions, e.g. from
+//!   [`SjtIter`](crate::SjtIter) as `(i, i + 1)`
+//! - [`verify_set_partitions`] - moves `(x, y)` of element `x` (counting from 1)
+//!   to block `y`, e.g. from [`SetPartitionIter`](crate::SetPartitionIter)
+//! - [`verify_all_set_partitions`] - the same for partitions into any number of
+//!   blocks, e.g. from [`SetPartitionAllIter`](crate::SetPartitionAllIter)
+//! - [`verify_set_biparts`] - moves of element `x` (counting from 1) to the other
+//!   block, e.g. from [`SetBipartIter`](crate::SetBipartIter)
+//!
+//! A move sequence may either stop at the last configuration, or close the
+//! cycle with one final move back to the initial configuration (as `sjt_gen`
+//! does); in the latter case the final move is not counted as a revisit.
+//!
+//! ## Complexity
+//!
+//! All functions take O(n log N) time per move and O(nN) space for a sequence
+//! of N configurations of size n.
+
+use alloc::collections::BTreeSet;
+use alloc::{vec, vec::Vec};
+
+use crate::combin::checked_comb;
+use crate::counting::checked_bell;
+use crate::perm::checked_factorial;
+use crate::set_bipart::checked_stirling2nd2;
+use crate::set_partition::checked_stirling2nd;
+
+/// Result of replaying a move sequence
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_combinations;
+/// use ecgen::EmkCombIter;
+///
+/// let report = verify_combinations(6, 3, EmkCombIter::new(6, 3));
+/// assert!(report.is_gray());
+/// assert_eq!(report.homogeneous, Some(true));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GrayReport {
+    /// The number of configurations visited, including the initial one.
+    pub visited: usize,
+    /// The number of distinct configurations visited.
+    pub distinct: usize,
+    /// The number of configurations there are, or `None` if it overflows `usize`.
+    pub expected: Option<usize>,
+    /// The index (counting from 0) of the first move reaching a configuration
+    /// that was already visited.
+    pub first_repeat: Option<usize>,
+    /// The index (counting from 0) of the first move that is not a minimal
+    /// change, e.g. an out-of-range position or a swap of two equal bits.
+    /// Such moves are skipped.
+    pub first_invalid: Option<usize>,
+    /// Whether the last configuration differs from the first by one minimal change.
+    pub cyclic: bool,
+    /// Whether every move was between adjacent positions (for set partitions,
+    /// between blocks with adjacent labels).
+    pub adjacent: bool,
+    /// For combinations, whether no move passes over an element of the
+    /// subset; `None` for the other structures.
+    pub homogeneous: Option<bool>,
+}
+
+impl GrayReport {
+    /// Whether every configuration was visited exactly once.
+    pub fn is_complete(&self) -> bool {
+        self.first_repeat.is_none() && Some(self.visited) == self.expected
+    }
+
+    /// Whether every move was a minimal change.
+    pub fn is_minimal_change(&self) -> bool {
+        self.first_invalid.is_none()
+    }
+
+    /// Whether the sequence is a Gray code: complete, with minimal changes only.
+    pub fn is_gray(&self) -> bool {
+        self.is_complete() && self.is_minimal_change()
+    }
+}
+
+/// The properties of a single move.
+struct Step {
+    valid: bool,
+    adjacent: bool,
+    homogeneous: bool,
+}
+
+const INVALID: Step = Step {
+    valid: false,
+    adjacent: true,
+    homogeneous: true,
+};
+
+/// Replays `moves` from `state`, identifying configurations by `key`; `near`
+/// tells whether two configurations differ by one minimal change.
+fn replay<S, K, M>(
+    mut state: S,
+    expected: Option<usize>,
+    moves: impl IntoIterator<Item = M>,
+    mut apply: impl FnMut(&mut S, M) -> Step,
+    key: impl Fn(&S) -> K,
+    near: impl Fn(&S, &S) -> bool,
+    has_homogeneous: bool,
+) -> GrayReport
+where
+    S: Clone,
+    K: Ord,
+{
+    let first = state.clone();
+    let first_key = key(&first);
+    let mut seen = BTreeSet::new();
+    let mut report = GrayReport {
+        visited: 1,
+        distinct: 1,
+        expected,
+        first_repeat: None,
+        first_invalid: None,
+        cyclic: false,
+        adjacent: true,
+        homogeneous: has_homogeneous.then_some(true),
+    };
+    // the index of a move back to the first configuration, which is a repeat
+    // only if more moves follow
+    let mut closing = None;
+    for (idx, m) in moves.into_iter().enumerate() {
+        if let Some(c) = closing.take() {
+            report.first_repeat = report.first_repeat.or(Some(c));
+            report.visited += 1;
+        }
+        let step = apply(&mut state, m);
+        if !step.valid {
+            report.first_invalid = report.first_invalid.or(Some(idx));
+            continue;
+        }
+        report.adjacent &= step.adjacent;
+        if let Some(h) = &mut report.homogeneous {
+            *h &= step.homogeneous;
+        }
+        let k = key(&state);
+        if k == first_key {
+            closing = Some(idx);
+        } else if seen.insert(k) {
+            report.visited += 1;
+            report.distinct += 1;
+        } else {
+            report.visited += 1;
+            report.first_repeat = report.first_repeat.or(Some(idx));
+        }
+    }
+    report.cyclic = if closing.is_some() {
+        true
+    } else {
+        report.visited > 1 && near(&state, &first)
+    };
+    report
+}
+
+/// Number of positions where `a` and `b` differ.
+fn hamming<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+/// The `verify_gray_code` function replays bit flips on an n-bit word starting from `0^n`.
+///
+/// Arguments:
+///
+/// * `n`: The number of bits.
+/// * `flips`: The positions of the bits to flip, e.g. from `brgc_gen` or [`BrgcIter`](crate::BrgcIter).
+///
+/// Returns:
+///
+/// A [`GrayReport`] with `expected` equal to `2^n`. Every flip is adjacent.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_gray_code;
+/// use ecgen::BrgcIter;
+///
+/// let report = verify_gray_code(4, BrgcIter::new(4));
+/// assert!(report.is_gray());
+/// assert!(report.cyclic);
+/// ```
+pub fn verify_gray_code(n: usize, flips: impl IntoIterator<Item = usize>) -> GrayReport {
+    let expected = u32::try_from(n).ok().and_then(|n| 1usize.checked_shl(n));
+    replay(
+        vec![false; n],
+        expected,
+        flips,
+        |bits: &mut Vec<bool>, i| match bits.get_mut(i) {
+            Some(b) => {
+                *b = !*b;
+                Step {
+                    valid: true,
+                    adjacent: true,
+                    homogeneous: true,
+                }
+            }
+            None => INVALID,
+        },
+        Vec::clone,
+        |a, b| hamming(a, b) == 1,
+        false,
+    )
+}
+
+/// The `verify_combinations` function replays swaps on a 0/1 vector starting from `1^k 0^(n-k)`.
+///
+/// A swap `(i, j)` is a minimal change if it exchanges a 1 and a 0. It is
+/// homogeneous if no 1 lies between positions `i` and `j`, and adjacent if
+/// `|i - j| = 1`.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+/// * `k`: The number of elements in each combination.
+/// * `swaps`: The swaps, e.g. from `emk_comb_gen` or [`EmkCombIter`](crate::EmkCombIter).
+///
+/// Returns:
+///
+/// A [`GrayReport`] with `expected` equal to `comb(n, k)`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_combinations;
+/// use ecgen::combin::ChaseIter;
+///
+/// let report = verify_combinations(7, 3, ChaseIter::new(7, 3));
+/// assert!(report.is_gray());
+/// assert!(!report.adjacent);
+/// ```
+pub fn verify_combinations(
+    n: usize,
+    k: usize,
+    swaps: impl IntoIterator<Item = (usize, usize)>,
+) -> GrayReport {
+    let bits: Vec<bool> = (0..n).map(|i| i < k).collect();
+    replay(
+        bits,
+        checked_comb(n, k),
+        swaps,
+        |bits: &mut Vec<bool>, (i, j)| {
+            if i >= bits.len() || j >= bits.len() || bits[i] == bits[j] {
+                return INVALID;
+            }
+            let (lo, hi) = (i.min(j), i.max(j));
+            let step = Step {
+                valid: true,
+                adjacent: hi - lo == 1,
+                homogeneous: !bits[lo + 1..hi].contains(&true),
+            };
+            bits.swap(i, j);
+            step
+        },
+        Vec::clone,
+        |a, b| hamming(a, b) == 2,
+        true,
+    )
+}
+
+/// The `verify_permutations` function replays transpositions of positions starting from the
+/// identity permutation of `n` elements.
+///
+/// A transposition `(i, j)` is a minimal change if `i != j`, and adjacent if
+/// `|i - j| = 1`.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+/// * `swaps`: The positions to swap, e.g. `(i, i + 1)` for each `i` from `sjt_gen`, or
+///   `(0, i)` for each `i` from `ehr_gen`.
+///
+/// Returns:
+///
+/// A [`GrayReport`] with `expected` equal to `factorial(n)`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_permutations;
+/// use ecgen::SjtIter;
+///
+/// let report = verify_permutations(5, SjtIter::new(5).map(|i| (i, i + 1)));
+/// assert!(report.is_gray());
+/// assert!(report.adjacent && report.cyclic);
+/// ```
+pub fn verify_permutations(
+    n: usize,
+    swaps: impl IntoIterator<Item = (usize, usize)>,
+) -> GrayReport {
+    replay(
+        (0..n).collect::<Vec<usize>>(),
+        checked_factorial(n),
+        swaps,
+        |perm: &mut Vec<usize>, (i, j)| {
+            if i >= perm.len() || j >= perm.len() || i == j {
+                return INVALID;
+            }
+            perm.swap(i, j);
+            Step {
+                valid: true,
+                adjacent: i.abs_diff(j) == 1,
+                homogeneous: true,
+            }
+        },
+        Vec::clone,
+        |a, b| hamming(a, b) == 2,
+        false,
+    )
+}
+
+/// Relabels the blocks of a labelled partition in order of first occurrence.
+fn canonical(labels: &[usize]) -> Vec<usize> {
+    let mut relabel: Vec<Option<usize>> = vec![None; labels.len() + 1];
+    let mut next = 0;
+    labels
+        .iter()
+        .map(|&b| {
+            *relabel[b].get_or_insert_with(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect()
+}
+
+/// Replays element moves on block labels less than `blocks`; with `fixed`,
+/// no block may become empty. `target` turns a move into the element to move
+/// (counting from 1) and its new block, given the current labels.
+fn replay_partition<M>(
+    labels: Vec<usize>,
+    blocks: usize,
+    fixed: bool,
+    expected: Option<usize>,
+    moves: impl IntoIterator<Item = M>,
+    target: impl Fn(&[usize], M) -> (usize, usize),
+) -> GrayReport {
+    let n = labels.len();
+    let mut sizes = vec![0; n + 1];
+    for &b in &labels {
+        sizes[b] += 1;
+    }
+    replay(
+        (labels, sizes),
+        expected,
+        moves,
+        |(labels, sizes): &mut (Vec<usize>, Vec<usize>), m| {
+            let (x, y) = target(labels, m);
+            if x == 0 || x > labels.len() || y >= blocks || labels[x - 1] == y {
+                return INVALID;
+            }
+            let from = labels[x - 1];
+            if fixed && sizes[from] == 1 {
+                return INVALID;
+            }
+            labels[x - 1] = y;
+            sizes[from] -= 1;
+            sizes[y] += 1;
+            Step {
+                valid: true,
+                adjacent: from.abs_diff(y) == 1,
+                homogeneous: true,
+            }
+        },
+        |(labels, _)| canonical(labels),
+        |(a, _), (b, _)| hamming(a, b) == 1,
+        false,
+    )
+}
+
+/// The `verify_set_partitions` function replays element moves on a partition of `n` elements
+/// into `k` blocks, starting from the block labels `0^(n-k) 0 1 ... (k-1)`.
+///
+/// A move `(x, y)` of element `x` (counting from 1) to block `y` is a minimal
+/// change if `x` was not in block `y` and its old block does not become empty.
+/// Partitions are compared up to relabelling of the blocks.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+/// * `k`: The number of blocks.
+/// * `moves`: The moves, e.g. from `set_partition_gen` or
+///   [`SetPartitionIter`](crate::SetPartitionIter).
+///
+/// Returns:
+///
+/// A [`GrayReport`] with `expected` equal to `stirling2nd(n, k)`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_set_partitions;
+/// use ecgen::SetPartitionIter;
+///
+/// let report = verify_set_partitions(6, 3, SetPartitionIter::new(6, 3));
+/// assert!(report.is_gray());
+/// ```
+pub fn verify_set_partitions(
+    n: usize,
+    k: usize,
+    moves: impl IntoIterator<Item = (usize, usize)>,
+) -> GrayReport {
+    let labels = (0..n).map(|i| (i + k).saturating_sub(n)).collect();
+    replay_partition(labels, k, true, checked_stirling2nd(n, k), moves, |_, m| m)
+}
+
+/// The `verify_all_set_partitions` function replays element moves on a partition of `n`
+/// elements into any number of blocks, starting from the single block `0^n`.
+///
+/// A move `(x, y)` of element `x` (counting from 1) to block `y < n` is a
+/// minimal change if `x` was not in block `y`. Partitions are compared up to
+/// relabelling of the blocks.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+/// * `moves`: The moves, e.g. from `set_partition_all_gen` or
+///   [`SetPartitionAllIter`](crate::SetPartitionAllIter).
+///
+/// Returns:
+///
+/// A [`GrayReport`] with `expected` equal to `bell(n)`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_all_set_partitions;
+/// use ecgen::SetPartitionAllIter;
+///
+/// let report = verify_all_set_partitions(6, SetPartitionAllIter::new(6));
+/// assert!(report.is_gray());
+/// ```
+pub fn verify_all_set_partitions(
+    n: usize,
+    moves: impl IntoIterator<Item = (usize, usize)>,
+) -> GrayReport {
+    replay_partition(vec![0; n], n, false, checked_bell(n), moves, |_, m| m)
+}
+
+/// The `verify_set_biparts` function replays element moves on a partition of `n` elements into
+/// two blocks, starting from the block labels `0^(n-1) 1`.
+///
+/// A move of element `x` (counting from 1) to the other block is a minimal
+/// change if the block it leaves does not become empty. Partitions are
+/// compared up to swapping the two blocks.
+///
+/// Arguments:
+///
+/// * `n`: The number of elements.
+/// * `moves`: The elements to move, e.g. from `set_bipart_gen` or
+///   [`SetBipartIter`](crate::SetBipartIter).
+///
+/// Returns:
+///
+/// A [`GrayReport`] with `expected` equal to `stirling2nd2(n)`.
+///
+/// # Examples
+///
+/// ```
+/// use ecgen::verify::verify_set_biparts;
+/// use ecgen::SetBipartIter;
+///
+/// let report = verify_set_biparts(6, SetBipartIter::new(6));
+/// assert!(report.is_gray());
+/// ```
+pub fn verify_set_biparts(n: usize, moves: impl IntoIterator<Item = usize>) -> GrayReport {
+    let labels: Vec<usize> = (0..n).map(|i| usize::from(i + 1 == n)).collect();
+    // the other block of x, read from the replayed labels; out-of-range
+    // elements are rejected by the replay
+    let flip = |labels: &[usize], x: usize| {
+        let to = labels.get(x.wrapping_sub(1)).map_or(0, |&b| 1 - b);
+        (x, to)
+    };
+    replay_partition(labels, 2, true, checked_stirling2nd2(n), moves, flip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combin::{ChaseIter, EmkCombIter, LiuTangIter};
+    use crate::gray_code::BrgcIter;
+    use crate::perm::{EhrIter, HeapIter, SjtIter};
+    use crate::set_bipart::SetBipartIter;
+    use crate::set_partition::{SetPartitionAllIter, SetPartitionIter};
+
+    #[test]
+    fn test_verify_gray_code() {
+        for n in 0..10 {
+            let report = verify_gray_code(n, BrgcIter::new(n));
+            assert!(report.is_gray(), "{report:?}");
+            assert_eq!(report.cyclic, n > 0);
+        }
+        let report = verify_gray_code(3, [0, 1, 0, 0]);
+        assert_eq!(report.first_repeat, Some(3));
+        assert!(!report.is_complete());
+        let report = verify_gray_code(2, [0, 5, 1, 0]);
+        assert_eq!(report.first_invalid, Some(1));
+        assert!(report.is_complete() && !report.is_gray());
+    }
+
+    #[test]
+    fn test_verify_combinations() {
+        for (n, k) in [(6, 3), (7, 2), (8, 5), (5, 1)] {
+            let report = verify_combinations(n, k, EmkCombIter::new(n, k));
+            assert!(report.is_gray(), "{report:?}");
+            assert_eq!(report.homogeneous, Some(true));
+            let report = verify_combinations(n, k, ChaseIter::new(n, k));
+            assert!(report.is_gray(), "{report:?}");
+            let report = verify_combinations(n, k, LiuTangIter::new(n, k));
+            assert!(report.is_gray(), "{report:?}");
+            assert!(report.cyclic);
+        }
+        assert_eq!(
+            verify_combinations(6, 3, LiuTangIter::new(6, 3)).homogeneous,
+            Some(false)
+        );
+        let report = verify_combinations(4, 2, [(0, 1)]);
+        assert_eq!(report.first_invalid, Some(0));
+    }
+
+    #[test]
+    fn test_verify_permutations() {
+        for n in 2..7 {
+            let report = verify_permutations(n, SjtIter::new(n).map(|i| (i, i + 1)));
+            assert!(report.is_gray(), "{report:?}");
+            assert!(report.adjacent && report.cyclic);
+            let report = verify_permutations(n, EhrIter::new(n).map(|i| (0, i)));
+            assert!(report.is_gray(), "{report:?}");
+            let report = verify_permutations(n, HeapIter::new(n));
+            assert!(report.is_gray(), "{report:?}");
+        }
+        // the closing swap of SJT is fine, but a further swap is a revisit
+        let swaps = SjtIter::new(3).map(|i| (i, i + 1)).chain(Some((0, 1)));
+        let report = verify_permutations(3, swaps);
+        assert_eq!(report.first_repeat, Some(5));
+    }
+
+    #[test]
+    fn test_verify_set_partitions() {
+        for (n, k) in [(5, 2), (6, 3), (7, 4), (6, 5)] {
+            let report = verify_set_partitions(n, k, SetPartitionIter::new(n, k));
+            assert!(report.is_gray(), "{report:?}");
+            assert_eq!(report.homogeneous, None);
+        }
+        for n in 2..9 {
+            let report = verify_set_biparts(n, SetBipartIter::new(n));
+            assert!(report.is_gray(), "{report:?}");
+        }
+        // a rejected move does not change the block of later moves: 0001,
+        // (4 would empty block 1), 1001, 1000
+        let report = verify_set_biparts(4, [4, 1, 4]);
+        assert_eq!(report.first_invalid, Some(0));
+        assert_eq!((report.visited, report.distinct), (3, 3));
+        // emptying a block is not a valid move
+        let report = verify_set_partitions(3, 2, [(3, 0)]);
+        assert_eq!(report.first_invalid, Some(0));
+        for n in 0..8 {
+            let report = verify_all_set_partitions(n, SetPartitionAllIter::new(n));
+            assert!(report.is_gray(), "{report:?}");
+        }
+    }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_verify_generators() {
+        use crate::{brgc_gen, emk_comb_gen, set_bipart_gen, set_partition_gen, sjt_gen};
+
+        assert!(verify_gray_code(5, brgc_gen(5)).is_gray());
+        assert!(verify_combinations(7, 3, emk_comb_gen(7, 3)).is_gray());
+        let report = verify_permutations(4, sjt_gen(4).into_iter().map(|i| (i, i + 1)));
+        assert!(report.is_gray() && report.cyclic);
+        assert!(verify_set_partitions(7, 3, set_partition_gen(7, 3)).is_gray());
+        assert!(verify_set_biparts(7, set_bipart_gen(7)).is_gray());
+    }
+}